@@ -0,0 +1,1068 @@
+use crate::error::Error;
+use crate::models::action::{ActionConfig, ActionStatus, ActionTimeouts, SendActionGoal};
+use crate::models::execution::{Execution, ExecutionEvent, ExecutionStatus};
+use crate::models::timeout::{ExecutionTimeouts, TimeoutLimit};
+use crate::registry::ExecutionRegistry;
+use arci_ros2::Node;
+use futures::{
+    future::BoxFuture,
+    stream::{BoxStream, StreamExt},
+    FutureExt,
+};
+use r2r::{
+    action_msgs::{msg::GoalInfo, srv::CancelGoal},
+    unique_identifier_msgs::msg::UUID,
+    GoalStatus,
+};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::{broadcast, oneshot};
+use tokio::time::timeout;
+use uuid::Uuid;
+
+/// Resolves to the goal UUID once the action server accepted the goal.
+pub type GoalAcceptance = oneshot::Receiver<Result<Uuid, Error>>;
+
+/// Goal accepted by the action server, with its result and feedback.
+pub type SentGoal<A> = (
+    <A as Action>::GoalHandle,
+    BoxFuture<'static, r2r::Result<(GoalStatus, <A as Action>::Result)>>,
+    BoxStream<'static, <A as Action>::Feedback>,
+);
+
+/// Checked once a goal has succeeded, e.g. until the joints reached the
+/// target. Resolves to the reason of the failure.
+pub type Completion = BoxFuture<'static, Result<(), String>>;
+
+/// Client of an action server driven by `ActionExecutor`, implemented by the
+/// typed clients of the actions with dedicated endpoints and by the untyped
+/// client of the actions configured with `[[actions]]`.
+pub trait Action: Clone + Send + Sync + 'static {
+    type Goal: Send + 'static;
+    type Feedback: Send + 'static;
+    type Result: Send + 'static;
+    type GoalHandle: GoalHandle;
+
+    fn is_available(&self, node: &Node) -> r2r::Result<BoxFuture<'static, r2r::Result<()>>>;
+
+    fn send_goal_request(
+        &self,
+        goal: Self::Goal,
+    ) -> r2r::Result<BoxFuture<'static, r2r::Result<SentGoal<Self>>>>;
+
+    /// Called once the action server is available, right before the goal is
+    /// sent. Returns how long the goal is expected to run, if known.
+    fn prepare_goal(_goal: &mut Self::Goal) -> Result<Option<Duration>, Error> {
+        Ok(None)
+    }
+
+    /// Converts a feedback message to the JSON pushed to the clients, or
+    /// returns `None` to skip the message.
+    fn feedback_to_json(feedback: Self::Feedback) -> Option<Value>;
+
+    fn outcome(result: Self::Result) -> GoalOutcome;
+}
+
+/// Goal sent by an `Action`.
+pub trait GoalHandle: Clone + Send + Sync + 'static {
+    fn uuid(&self) -> Uuid;
+    fn get_status(&self) -> r2r::Result<GoalStatus>;
+    fn cancel(&self) -> r2r::Result<BoxFuture<'static, r2r::Result<()>>>;
+}
+
+/// Result of a goal, as recorded in the `ExecutionRegistry`.
+pub struct GoalOutcome {
+    pub error_code: Option<i32>,
+    pub error_string: String,
+    pub result: Option<Value>,
+}
+
+/// Progress of a goal sent by `ActionExecutor::run_goal`, shared by the goal
+/// task and the watchdog.
+enum GoalPhase {
+    Requested,
+    Accepted {
+        goal_id: Uuid,
+        at: Instant,
+    },
+    /// the watchdog stopped waiting for the acceptance
+    Abandoned,
+}
+
+/// Limits of the executor watchdog, resolved when the goal is submitted.
+#[derive(Debug, Clone, Copy)]
+pub struct WatchdogLimits {
+    pub availability: Duration,
+    /// not checked if `None`
+    pub feedback_silence: Option<Duration>,
+    /// added to the expected duration of the goal
+    pub duration_margin: Duration,
+    /// applied when the expected duration of the goal is unknown; not checked
+    /// if `None`
+    pub max_duration: Option<Duration>,
+    pub poll_interval: Duration,
+}
+
+impl TryFrom<&ExecutionTimeouts> for WatchdogLimits {
+    type Error = Error;
+
+    fn try_from(timeouts: &ExecutionTimeouts) -> Result<Self, Error> {
+        Ok(Self {
+            availability: timeouts.availability()?,
+            feedback_silence: Some(timeouts.feedback_silence()?),
+            duration_margin: timeouts.duration_margin()?,
+            max_duration: None,
+            poll_interval: timeouts.poll_interval()?,
+        })
+    }
+}
+
+impl TryFrom<&ActionTimeouts> for WatchdogLimits {
+    type Error = Error;
+
+    fn try_from(timeouts: &ActionTimeouts) -> Result<Self, Error> {
+        Ok(Self {
+            availability: timeouts.availability()?,
+            feedback_silence: timeouts.feedback_silence()?,
+            duration_margin: Duration::ZERO,
+            max_duration: timeouts.duration()?,
+            poll_interval: timeouts.poll_interval()?,
+        })
+    }
+}
+
+/// Sends goals to an action server and supervises them: feedback and
+/// results go to the `ExecutionRegistry`, and the watchdog asks the action
+/// server to cancel the goals exceeding their `WatchdogLimits` and reports
+/// them as timed out once it answered.
+#[derive(Clone)]
+pub struct ActionExecutor<A: Action> {
+    client: A,
+    name: String,
+    action_name: String,
+    node: Node,
+    current_goal: Arc<Mutex<Option<A::GoalHandle>>>,
+    active_goals: Arc<Mutex<HashMap<Uuid, A::GoalHandle>>>,
+    // active goals whose cancel request is not answered yet
+    canceling: Arc<Mutex<HashSet<Uuid>>>,
+    executions: ExecutionRegistry,
+}
+
+impl<A: Action> ActionExecutor<A> {
+    pub fn new(
+        node: Node,
+        name: &str,
+        action_name: &str,
+        client: A,
+        executions: ExecutionRegistry,
+    ) -> Self {
+        Self {
+            client,
+            name: name.to_string(),
+            action_name: action_name.to_string(),
+            node,
+            current_goal: Arc::new(Mutex::new(None)),
+            active_goals: Arc::new(Mutex::new(HashMap::new())),
+            canceling: Arc::new(Mutex::new(HashSet::new())),
+            executions,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn action_name(&self) -> &str {
+        &self.action_name
+    }
+
+    pub fn executions(&self) -> &ExecutionRegistry {
+        &self.executions
+    }
+
+    /// Returns the last goal sent, until it finishes or is canceled.
+    pub fn current_goal(&self) -> Option<Uuid> {
+        self.current_goal.lock().unwrap().as_ref().map(|g| g.uuid())
+    }
+
+    pub fn active_goals(&self) -> Vec<Uuid> {
+        self.active_goals.lock().unwrap().keys().copied().collect()
+    }
+
+    /// Sends `goal` once the action server is available and supervises it
+    /// until it finishes. `acceptance_tx` receives the goal UUID, or the
+    /// reason the goal was not accepted.
+    pub async fn run_goal(
+        &self,
+        mut goal: A::Goal,
+        completion: Option<Completion>,
+        limits: WatchdogLimits,
+        queue_id: Option<Uuid>,
+        acceptance_tx: oneshot::Sender<Result<Uuid, Error>>,
+    ) {
+        let current_goal = self.current_goal.clone();
+        let active_goals = self.active_goals.clone();
+        let executions = self.executions.clone();
+        let client = self.client.clone();
+        let name = self.name.clone();
+
+        // wait for action server to be available
+        log::info!("waiting for action server {}...", self.action_name);
+        let is_available = match self.client.is_available(&self.node) {
+            Ok(is_available) => is_available,
+            Err(e) => {
+                let _ = acceptance_tx.send(Err(e.into()));
+                return;
+            }
+        };
+        match timeout(limits.availability, is_available).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                log::error!("failed to wait for action server: {:?}", e);
+                let _ = acceptance_tx.send(Err(e.into()));
+                return;
+            }
+            Err(e) => {
+                log::error!("action server is not available: {:?}", e);
+                let _ =
+                    acceptance_tx.send(Err(Error::ActionServerUnavailable(limits.availability)));
+                return;
+            }
+        }
+
+        // the goal may run until its expected end plus a margin
+        let total_duration = match A::prepare_goal(&mut goal) {
+            Ok(expected_duration) => expected_duration
+                .map(|expected| expected + limits.duration_margin)
+                .or(limits.max_duration),
+            Err(e) => {
+                let _ = acceptance_tx.send(Err(e));
+                return;
+            }
+        };
+        log::info!("action server is available");
+
+        let last_update_time = Arc::new(Mutex::new(Instant::now()));
+        let last_update_time_clone = last_update_time.clone();
+
+        let is_done = Arc::new(AtomicBool::new(false));
+        let is_done_clone = is_done.clone();
+        let is_result_received = Arc::new(AtomicBool::new(false));
+        let is_result_received_clone = is_result_received.clone();
+        let phase = Arc::new(Mutex::new(GoalPhase::Requested));
+        let phase_clone = phase.clone();
+        let current_goal_clone = current_goal.clone();
+        let active_goals_clone = active_goals.clone();
+        let executions_clone = executions.clone();
+
+        let (cancel_tx, mut cancel_rx1) = broadcast::channel(1);
+        let mut cancel_rx2 = cancel_tx.subscribe();
+
+        // spawn a task to handle goal request
+        let requested_at = Instant::now();
+        let goal_task = tokio::spawn(async move {
+            let send_goal_request = match client.send_goal_request(goal) {
+                Ok(send_goal_request) => send_goal_request,
+                Err(e) => {
+                    log::error!("failed to send goal request: {:?}", e);
+                    executions_clone.rejected(e.to_string());
+                    let _ = acceptance_tx.send(Err(Error::from_untyped(e)));
+                    is_done.store(true, Ordering::Relaxed);
+                    return;
+                }
+            };
+
+            let (goal, result, feedback) = match send_goal_request.await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    log::error!("goal rejected by action server: {:?}", e);
+                    executions_clone.rejected(e.to_string());
+                    let _ = acceptance_tx.send(Err(Error::GoalRejected(e.to_string())));
+                    is_done.store(true, Ordering::Relaxed);
+                    return;
+                }
+            };
+
+            // the watchdog limits apply from the acceptance
+            let goal_id = goal.uuid();
+            let abandoned = {
+                let mut phase = phase_clone.lock().unwrap();
+                if matches!(*phase, GoalPhase::Abandoned) {
+                    true
+                } else {
+                    current_goal_clone.lock().unwrap().replace(goal.clone());
+                    active_goals_clone
+                        .lock()
+                        .unwrap()
+                        .insert(goal_id, goal.clone());
+                    let now = Instant::now();
+                    *last_update_time.lock().unwrap() = now;
+                    *phase = GoalPhase::Accepted { goal_id, at: now };
+                    false
+                }
+            };
+
+            log::info!("goal_accepted: {}", goal_id);
+            executions_clone.accepted(goal_id, &name, queue_id);
+
+            // the watchdog gave up waiting, so nothing supervises the goal
+            if abandoned {
+                log::warn!("goal {} was accepted after the watchdog gave up", goal_id);
+                let error_string = format!("not accepted within {:?}", limits.availability);
+                let error_string = match cancel_timed_out(&goal, limits.availability).await {
+                    Ok(()) => error_string,
+                    Err(e) => {
+                        log::error!("failed to cancel timed out goal {}: {:?}", goal_id, e);
+                        format!("{}, failed to cancel: {}", error_string, e)
+                    }
+                };
+                executions_clone.timed_out(&goal_id, TimeoutLimit::Acceptance, error_string);
+                let _ = acceptance_tx.send(Err(Error::GoalNotAccepted(limits.availability)));
+                is_done.store(true, Ordering::Relaxed);
+                return;
+            }
+
+            let _ = acceptance_tx.send(Ok(goal_id));
+            let feedback_executions = executions_clone.clone();
+
+            // spawn a task to handle feedback
+            tokio::spawn(async move {
+                // wait for feedback
+                tokio::select! {
+                    _ = feedback
+                        .for_each(|msg| {
+                            // update last_update_time_nsec
+                            let now = Instant::now();
+                            *last_update_time.lock().unwrap() = now;
+
+                            log::debug!("feedback: {} -- {:?}", goal_id, goal.get_status());
+
+                            if let Ok(status) = goal.get_status() {
+                                feedback_executions.set_status(&goal_id, status.into());
+                            }
+                            if let Some(feedback) = A::feedback_to_json(msg) {
+                                feedback_executions.feedback(&goal_id, feedback);
+                            }
+                            std::future::ready(())
+                        }) => {
+                            log::info!("feedback finished");
+                        }
+                    v = cancel_rx1.recv() => {
+                        match v {
+                            Ok(_) => {
+                                log::info!("feedback cancel_rx.recv() finished");
+                            }
+                            Err(broadcast::error::RecvError::Closed) => {
+                                log::debug!("feedback cancel_rx.recv() closed");
+                            }
+                            Err(e) => {
+                                log::error!("feedback cancel_rx.recv() error: {:?}", e);
+                            }
+                        }
+                    }
+                }
+            });
+
+            // wait for result
+            let finished = tokio::select! {
+                r = result => {
+                    match r {
+                        Ok((status, msg)) => {
+                            log::info!("Got result {} of {}", status, goal_id);
+                            let mut execution_status = ExecutionStatus::from(status);
+                            let outcome = A::outcome(msg);
+                            let mut error_string = outcome.error_string;
+
+                            // e.g. wait until the measured joints converge
+                            let completion =
+                                completion.filter(|_| status == GoalStatus::Succeeded);
+                            if let Some(completion) = completion {
+                                is_result_received.store(true, Ordering::Relaxed);
+                                match completion.await {
+                                    Ok(()) => {
+                                        log::info!("complete condition satisfied");
+                                    }
+                                    Err(e) => {
+                                        log::error!("complete condition failed: {}", e);
+                                        execution_status = ExecutionStatus::Aborted;
+                                        error_string = e;
+                                    }
+                                }
+                            }
+
+                            executions_clone.finished(
+                                &goal_id,
+                                execution_status,
+                                outcome.error_code,
+                                error_string,
+                                outcome.result,
+                            );
+                        }
+                        Err(e) => {
+                            log::error!("Action failed: {:?}", e);
+                            executions_clone.finished(
+                                &goal_id,
+                                ExecutionStatus::Aborted,
+                                None,
+                                e.to_string(),
+                                None,
+                            );
+                        }
+                    }
+                    is_done.store(true, Ordering::Relaxed);
+                    true
+                }
+                v = cancel_rx2.recv() => {
+                    match v {
+                        Ok(_) => {
+                            log::info!("wait result cancel_rx.recv() finished");
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            log::debug!("wait result cancel_rx.recv() closed");
+                        }
+                        Err(e) => {
+                            log::error!("wait result cancel_rx.recv() error: {:?}", e);
+                        }
+                    }
+                    false
+                }
+            };
+
+            // a timed out goal stays active until the action server answered
+            // the cancel request of the watchdog
+            if finished {
+                active_goals_clone.lock().unwrap().remove(&goal_id);
+            }
+        });
+
+        // check if action is completed or timed out
+        loop {
+            tokio::time::sleep(limits.poll_interval).await;
+
+            // check if action is completed
+            if is_done_clone.load(Ordering::Relaxed) {
+                log::info!("action completed");
+                break;
+            }
+
+            // no feedback is expected while waiting for the complete condition
+            if is_result_received_clone.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            // wait for the acceptance at most as long as for the action server
+            let (goal_id, start_time) = {
+                let mut phase = phase.lock().unwrap();
+                match *phase {
+                    GoalPhase::Accepted { goal_id, at } => (goal_id, at),
+                    GoalPhase::Requested | GoalPhase::Abandoned => {
+                        if requested_at.elapsed() < limits.availability {
+                            continue;
+                        }
+                        log::warn!("goal was not accepted within {:?}", limits.availability);
+                        // the goal task cancels the goal if it is accepted later
+                        *phase = GoalPhase::Abandoned;
+                        break;
+                    }
+                }
+            };
+
+            // check if action is timed out
+            let last_update_time = *last_update_time_clone.lock().unwrap();
+            let now = Instant::now();
+            let elapsed_from_last_update = now - last_update_time;
+            let elapsed_from_start = now - start_time;
+
+            let timed_out = match (limits.feedback_silence, total_duration) {
+                (Some(silence), _) if elapsed_from_last_update >= silence => Some((
+                    TimeoutLimit::FeedbackSilence,
+                    format!("no feedback for {:?}", silence),
+                )),
+                (_, Some(total)) if elapsed_from_start >= total => Some((
+                    TimeoutLimit::TotalDuration,
+                    format!("still running after {:?}", total),
+                )),
+                _ => None,
+            };
+
+            // the action server is deciding about a cancel request of a client
+            if timed_out.is_some() && self.canceling.lock().unwrap().contains(&goal_id) {
+                continue;
+            }
+
+            if let Some((limit, error_string)) = timed_out {
+                log::warn!("action timed out: {} limit, {}", limit, error_string);
+                // the outcome is decided here, so stop waiting for the result;
+                // the receivers are gone if the goal has just finished
+                let _ = cancel_tx.send("cancel");
+
+                // a goal canceled by a client is no longer active
+                let goal = active_goals.lock().unwrap().get(&goal_id).cloned();
+                if let Some(goal) = goal {
+                    let error_string = match cancel_timed_out(&goal, limits.availability).await {
+                        Ok(()) => error_string,
+                        Err(e) => {
+                            log::error!("failed to cancel timed out goal {}: {:?}", goal.uuid(), e);
+                            format!("{}, failed to cancel: {}", error_string, e)
+                        }
+                    };
+                    executions.timed_out(&goal.uuid(), limit, error_string);
+                    active_goals.lock().unwrap().remove(&goal.uuid());
+                }
+                break;
+            }
+        }
+
+        // the next goal is not sent before this one is settled
+        if goal_task.await.is_err() {
+            log::error!("goal task of {} panicked", self.name);
+        }
+
+        // clear current_goal
+        let phase = phase.lock().unwrap();
+        if let GoalPhase::Accepted { goal_id, .. } = *phase {
+            let mut current_goal = current_goal.lock().unwrap();
+            if current_goal.as_ref().is_some_and(|g| g.uuid() == goal_id) {
+                current_goal.take();
+            }
+        }
+    }
+
+    /// Cancels every goal accepted by the action server.
+    pub fn cancel_all(&self) -> Vec<tokio::task::JoinHandle<Result<(), Error>>> {
+        self.current_goal.lock().unwrap().take();
+        let goals: Vec<_> = self
+            .active_goals
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect();
+        goals
+            .into_iter()
+            .filter_map(|goal| match self.cancel(goal) {
+                Ok(handler) => Some(handler),
+                Err(Error::GoalCanceling(_)) => None,
+                Err(e) => {
+                    log::error!("{}: failed to cancel goal: {:?}", self.name, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    pub fn cancel_goal(&self) -> Result<tokio::task::JoinHandle<Result<(), Error>>, Error> {
+        if let Some(current_goal) = self.current_goal.lock().unwrap().take() {
+            return self.cancel(current_goal);
+        }
+
+        Err(Error::NoValidGoalExists)
+    }
+
+    pub fn cancel_goal_by_id(
+        &self,
+        goal_id: &Uuid,
+    ) -> Result<tokio::task::JoinHandle<Result<(), Error>>, Error> {
+        let goal = self.active_goals.lock().unwrap().get(goal_id).cloned();
+        match (goal, self.executions.get(goal_id)) {
+            (Some(goal), _) => {
+                let mut current_goal = self.current_goal.lock().unwrap();
+                if current_goal.as_ref().is_some_and(|g| g.uuid() == *goal_id) {
+                    current_goal.take();
+                }
+                drop(current_goal);
+                self.cancel(goal)
+            }
+            // the action server accepted the cancel request, the result is pending
+            (None, Some(execution)) if execution.status == ExecutionStatus::Canceling => {
+                Err(Error::GoalCanceling(*goal_id))
+            }
+            (None, Some(_)) => Err(Error::GoalAlreadyFinished(*goal_id)),
+            (None, None) => Err(Error::GoalNotFound(*goal_id)),
+        }
+    }
+
+    /// Asks the action server to cancel the goal `goal_id`, e.g. a goal sent
+    /// by another process of the gateway.
+    pub fn cancel_goal_on_server(
+        &self,
+        goal_id: Uuid,
+        availability: Duration,
+    ) -> Result<tokio::task::JoinHandle<Result<(), Error>>, Error> {
+        let service_name = format!("{}/_action/cancel_goal", self.action_name);
+        let mut node = self.node.r2r();
+        let client = node.create_client::<CancelGoal::Service>(&service_name)?;
+        let is_available = node.is_available(&client)?;
+        drop(node);
+
+        Ok(tokio::spawn(async move {
+            match timeout(availability, is_available).await {
+                Ok(res) => res?,
+                Err(_) => return Err(Error::ActionServerUnavailable(availability)),
+            }
+            // a zero stamp cancels only the goal with the given id
+            let request = CancelGoal::Request {
+                goal_info: GoalInfo {
+                    goal_id: UUID {
+                        uuid: goal_id.as_bytes().to_vec(),
+                    },
+                    ..Default::default()
+                },
+            };
+            let response = client.request(&request)?.await?;
+            match response.return_code {
+                CANCEL_ERROR_NONE => {
+                    log::warn!("canceling goal: {:?}", goal_id);
+                    Ok(())
+                }
+                CANCEL_ERROR_UNKNOWN_GOAL_ID => Err(Error::GoalNotFound(goal_id)),
+                CANCEL_ERROR_GOAL_TERMINATED => Err(Error::GoalAlreadyFinished(goal_id)),
+                code => Err(Error::Other(anyhow::anyhow!(
+                    "cancel request was rejected with return code {}",
+                    code
+                ))),
+            }
+        }))
+    }
+
+    fn cancel(
+        &self,
+        goal: A::GoalHandle,
+    ) -> Result<tokio::task::JoinHandle<Result<(), Error>>, Error> {
+        let goal_id = goal.uuid();
+        if !self.canceling.lock().unwrap().insert(goal_id) {
+            return Err(Error::GoalCanceling(goal_id));
+        }
+        log::warn!("cancel goal: {:?}", goal_id);
+        let fut = match goal.cancel() {
+            Ok(fut) => fut,
+            Err(e) => {
+                self.canceling.lock().unwrap().remove(&goal_id);
+                return Err(e.into());
+            }
+        };
+        // the goal stays active, but not timed out by the watchdog, until the
+        // action server answered
+        let active_goals = self.active_goals.clone();
+        let canceling = self.canceling.clone();
+        let executions = self.executions.clone();
+        let cancel_handler = tokio::spawn(async move {
+            let res = match fut.await {
+                Ok(()) => {
+                    log::warn!("canceled goal: {:?}", goal_id);
+                    executions.canceled(&goal_id);
+                    active_goals.lock().unwrap().remove(&goal_id);
+                    Ok(())
+                }
+                Err(e) => {
+                    log::error!("failed to cancel goal {:?}: {:?}", goal_id, e);
+                    Err(e.into())
+                }
+            };
+            canceling.lock().unwrap().remove(&goal_id);
+            res
+        });
+
+        Ok(cancel_handler)
+    }
+}
+
+/// Asks the action server to cancel a goal stopped by the watchdog, waiting
+/// at most `wait` for the response.
+async fn cancel_timed_out<G: GoalHandle>(goal: &G, wait: Duration) -> Result<(), Error> {
+    log::warn!("cancel timed out goal: {:?}", goal.uuid());
+    let response = goal.cancel()?;
+    match timeout(wait, response).await {
+        Ok(res) => Ok(res?),
+        Err(_) => Err(Error::ActionServerUnavailable(wait)),
+    }
+}
+
+// return codes of action_msgs/srv/CancelGoal
+const CANCEL_ERROR_NONE: i8 = 0;
+const CANCEL_ERROR_UNKNOWN_GOAL_ID: i8 = 2;
+const CANCEL_ERROR_GOAL_TERMINATED: i8 = 3;
+
+impl<T: r2r::WrappedActionTypeSupport> GoalHandle for r2r::ActionClientGoal<T> {
+    fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn get_status(&self) -> r2r::Result<GoalStatus> {
+        r2r::ActionClientGoal::get_status(self)
+    }
+
+    fn cancel(&self) -> r2r::Result<BoxFuture<'static, r2r::Result<()>>> {
+        Ok(r2r::ActionClientGoal::cancel(self)?.boxed())
+    }
+}
+
+impl GoalHandle for r2r::ActionClientGoalUntyped {
+    fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn get_status(&self) -> r2r::Result<GoalStatus> {
+        r2r::ActionClientGoalUntyped::get_status(self)
+    }
+
+    fn cancel(&self) -> r2r::Result<BoxFuture<'static, r2r::Result<()>>> {
+        Ok(r2r::ActionClientGoalUntyped::cancel(self)?.boxed())
+    }
+}
+
+/// Goals, feedback and results as JSON, converted by r2r from the type
+/// given at runtime.
+impl Action for r2r::ActionClientUntyped {
+    type Goal = Value;
+    type Feedback = r2r::Result<Value>;
+    type Result = r2r::Result<Value>;
+    type GoalHandle = r2r::ActionClientGoalUntyped;
+
+    fn is_available(&self, node: &Node) -> r2r::Result<BoxFuture<'static, r2r::Result<()>>> {
+        Ok(node.r2r().is_available(self)?.boxed())
+    }
+
+    fn send_goal_request(
+        &self,
+        goal: Value,
+    ) -> r2r::Result<BoxFuture<'static, r2r::Result<SentGoal<Self>>>> {
+        let request = r2r::ActionClientUntyped::send_goal_request(self, goal)?;
+        Ok(async move {
+            let (goal, result, feedback) = request.await?;
+            Ok((goal, result.boxed(), feedback.boxed()))
+        }
+        .boxed())
+    }
+
+    fn feedback_to_json(feedback: r2r::Result<Value>) -> Option<Value> {
+        match feedback {
+            Ok(feedback) => Some(feedback),
+            Err(e) => {
+                log::warn!("failed to convert feedback: {:?}", e);
+                None
+            }
+        }
+    }
+
+    fn outcome(result: r2r::Result<Value>) -> GoalOutcome {
+        match result {
+            // results following the FollowJointTrajectory convention
+            Ok(result) => GoalOutcome {
+                error_code: result
+                    .get("error_code")
+                    .and_then(Value::as_i64)
+                    .map(|code| code as i32),
+                error_string: result
+                    .get("error_string")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                result: Some(result),
+            },
+            Err(e) => GoalOutcome {
+                error_code: None,
+                error_string: format!("failed to convert result: {}", e),
+                result: None,
+            },
+        }
+    }
+}
+
+/// An action of the `[[actions]]` config, driven with JSON goals over REST.
+/// Goals are sent as soon as they are submitted; the action server decides
+/// whether they run concurrently.
+pub struct JsonActionExecutor {
+    executor: ActionExecutor<r2r::ActionClientUntyped>,
+    config: ActionConfig,
+}
+
+impl JsonActionExecutor {
+    pub fn new(
+        node: Node,
+        config: &ActionConfig,
+        executions: ExecutionRegistry,
+    ) -> Result<Self, Error> {
+        let client = node
+            .r2r()
+            .create_action_client_untyped(&config.action_name, &config.action_type)?;
+        log::info!(
+            "action {}: {} ({})",
+            config.name,
+            config.action_name,
+            config.action_type
+        );
+        Ok(Self {
+            executor: ActionExecutor::new(
+                node,
+                &config.name,
+                &config.action_name,
+                client,
+                executions,
+            ),
+            config: config.clone(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        self.executor.name()
+    }
+
+    pub fn status(&self) -> ActionStatus {
+        ActionStatus {
+            name: self.config.name.clone(),
+            action_name: self.config.action_name.clone(),
+            action_type: self.config.action_type.clone(),
+            timeouts: self.config.timeouts,
+            active_goals: self.executor.active_goals(),
+        }
+    }
+
+    pub fn send_goal(&self, request: SendActionGoal) -> Result<GoalAcceptance, Error> {
+        let timeouts = self.config.timeouts.with_overrides(&request.timeouts);
+        let limits = WatchdogLimits::try_from(&timeouts)?;
+        let (acceptance_tx, acceptance_rx) = oneshot::channel();
+        let executor = self.executor.clone();
+        tokio::spawn(async move {
+            executor
+                .run_goal(request.goal, None, limits, None, acceptance_tx)
+                .await;
+        });
+        Ok(acceptance_rx)
+    }
+
+    /// Returns the execution of a goal sent by this action, finished or not.
+    /// The registry is shared with the controllers, so the goals of others
+    /// are not found.
+    pub fn goal(&self, goal_id: &Uuid) -> Result<Execution, Error> {
+        self.executor
+            .executions()
+            .get(goal_id)
+            .filter(|execution| execution.controller == self.config.name)
+            .ok_or(Error::GoalNotFound(*goal_id))
+    }
+
+    /// Subscribes to the events of a running goal. Returns `None` if the goal
+    /// has already finished.
+    pub fn subscribe_goal(
+        &self,
+        goal_id: &Uuid,
+    ) -> Result<Option<broadcast::Receiver<ExecutionEvent>>, Error> {
+        self.goal(goal_id)?;
+        Ok(self.executor.executions().subscribe(goal_id))
+    }
+
+    pub fn cancel_goal_by_id(
+        &self,
+        goal_id: &Uuid,
+    ) -> Result<tokio::task::JoinHandle<Result<(), Error>>, Error> {
+        self.goal(goal_id)?;
+        self.executor.cancel_goal_by_id(goal_id)
+    }
+
+    pub fn cancel_all(&self) -> Vec<tokio::task::JoinHandle<Result<(), Error>>> {
+        self.executor.cancel_all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Action server accepting every goal after `accept_after`, which then
+    /// succeeds after `succeed_after` if set.
+    #[derive(Clone)]
+    struct DelayedAction {
+        goal_id: Uuid,
+        accept_after: Duration,
+        succeed_after: Option<Duration>,
+        cancel_after: Duration,
+        canceled: Arc<AtomicBool>,
+    }
+
+    impl DelayedAction {
+        fn new(accept_after: Duration, succeed_after: Option<Duration>) -> Self {
+            Self {
+                goal_id: Uuid::new_v4(),
+                accept_after,
+                succeed_after,
+                cancel_after: Duration::ZERO,
+                canceled: Arc::new(AtomicBool::new(false)),
+            }
+        }
+    }
+
+    #[derive(Clone)]
+    struct DelayedGoal {
+        goal_id: Uuid,
+        cancel_after: Duration,
+        canceled: Arc<AtomicBool>,
+    }
+
+    impl GoalHandle for DelayedGoal {
+        fn uuid(&self) -> Uuid {
+            self.goal_id
+        }
+
+        fn get_status(&self) -> r2r::Result<GoalStatus> {
+            Ok(GoalStatus::Executing)
+        }
+
+        fn cancel(&self) -> r2r::Result<BoxFuture<'static, r2r::Result<()>>> {
+            let cancel_after = self.cancel_after;
+            let canceled = self.canceled.clone();
+            Ok(async move {
+                tokio::time::sleep(cancel_after).await;
+                canceled.store(true, Ordering::Relaxed);
+                Ok(())
+            }
+            .boxed())
+        }
+    }
+
+    impl Action for DelayedAction {
+        type Goal = ();
+        type Feedback = ();
+        type Result = ();
+        type GoalHandle = DelayedGoal;
+
+        fn is_available(&self, _node: &Node) -> r2r::Result<BoxFuture<'static, r2r::Result<()>>> {
+            Ok(futures::future::ready(Ok(())).boxed())
+        }
+
+        fn send_goal_request(
+            &self,
+            _goal: (),
+        ) -> r2r::Result<BoxFuture<'static, r2r::Result<SentGoal<Self>>>> {
+            let action = self.clone();
+            Ok(async move {
+                tokio::time::sleep(action.accept_after).await;
+                let goal = DelayedGoal {
+                    goal_id: action.goal_id,
+                    cancel_after: action.cancel_after,
+                    canceled: action.canceled,
+                };
+                let result = match action.succeed_after {
+                    Some(after) => async move {
+                        tokio::time::sleep(after).await;
+                        Ok((GoalStatus::Succeeded, ()))
+                    }
+                    .boxed(),
+                    None => futures::future::pending().boxed(),
+                };
+                Ok((goal, result, futures::stream::pending().boxed()))
+            }
+            .boxed())
+        }
+
+        fn feedback_to_json(_feedback: ()) -> Option<Value> {
+            None
+        }
+
+        fn outcome(_result: ()) -> GoalOutcome {
+            GoalOutcome {
+                error_code: None,
+                error_string: String::new(),
+                result: None,
+            }
+        }
+    }
+
+    fn executor(action: DelayedAction) -> ActionExecutor<DelayedAction> {
+        let node = Node::new("test_action_executor", "").unwrap();
+        ActionExecutor::new(node, "test", "/test", action, ExecutionRegistry::new())
+    }
+
+    fn limits(availability: Duration, feedback_silence: Duration) -> WatchdogLimits {
+        WatchdogLimits {
+            availability,
+            feedback_silence: Some(feedback_silence),
+            duration_margin: Duration::ZERO,
+            max_duration: None,
+            poll_interval: Duration::from_millis(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn limits_apply_from_acceptance() {
+        // accepted after the feedback silence limit, succeeds before it ends
+        let action =
+            DelayedAction::new(Duration::from_millis(300), Some(Duration::from_millis(50)));
+        let goal_id = action.goal_id;
+        let executor = executor(action);
+        let (acceptance_tx, acceptance_rx) = oneshot::channel();
+        let limits = limits(Duration::from_secs(2), Duration::from_millis(200));
+        executor
+            .run_goal((), None, limits, None, acceptance_tx)
+            .await;
+
+        assert_eq!(acceptance_rx.await.unwrap().unwrap(), goal_id);
+        let execution = executor.executions().get(&goal_id).unwrap();
+        assert_eq!(execution.status, ExecutionStatus::Succeeded);
+        assert_eq!(execution.timeout, None);
+        assert!(executor.active_goals().is_empty());
+        assert_eq!(executor.current_goal(), None);
+    }
+
+    #[tokio::test]
+    async fn goal_accepted_after_timeout_is_canceled() {
+        let action = DelayedAction::new(Duration::from_millis(200), None);
+        let goal_id = action.goal_id;
+        let canceled = action.canceled.clone();
+        let executor = executor(action);
+        let (acceptance_tx, acceptance_rx) = oneshot::channel();
+        let limits = limits(Duration::from_millis(50), Duration::from_secs(10));
+        executor
+            .run_goal((), None, limits, None, acceptance_tx)
+            .await;
+
+        // run_goal returns once the late goal is settled
+        assert!(matches!(
+            acceptance_rx.await.unwrap(),
+            Err(Error::GoalNotAccepted(_))
+        ));
+        assert!(canceled.load(Ordering::Relaxed));
+        let execution = executor.executions().get(&goal_id).unwrap();
+        assert_eq!(execution.status, ExecutionStatus::TimedOut);
+        assert_eq!(execution.timeout, Some(TimeoutLimit::Acceptance));
+        assert!(executor.active_goals().is_empty());
+        assert_eq!(executor.current_goal(), None);
+    }
+
+    #[tokio::test]
+    async fn goal_stays_active_until_cancel_is_answered() {
+        let mut action = DelayedAction::new(Duration::ZERO, None);
+        action.cancel_after = Duration::from_millis(100);
+        let goal_id = action.goal_id;
+        let executor = executor(action);
+        let (acceptance_tx, acceptance_rx) = oneshot::channel();
+        let limits = limits(Duration::from_secs(1), Duration::from_secs(10));
+        let runner = executor.clone();
+        tokio::spawn(async move { runner.run_goal((), None, limits, None, acceptance_tx).await });
+        assert_eq!(acceptance_rx.await.unwrap().unwrap(), goal_id);
+
+        let handler = executor.cancel_goal_by_id(&goal_id).unwrap();
+        assert_eq!(executor.active_goals(), vec![goal_id]);
+        assert!(matches!(
+            executor.cancel_goal_by_id(&goal_id),
+            Err(Error::GoalCanceling(_))
+        ));
+
+        handler.await.unwrap().unwrap();
+        assert!(executor.active_goals().is_empty());
+        let execution = executor.executions().get(&goal_id).unwrap();
+        assert_eq!(execution.status, ExecutionStatus::Canceling);
+        // the result is still pending
+        assert!(matches!(
+            executor.cancel_goal_by_id(&goal_id),
+            Err(Error::GoalCanceling(_))
+        ));
+    }
+}
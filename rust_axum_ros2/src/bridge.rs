@@ -0,0 +1,282 @@
+use crate::error::Error;
+use crate::models::topic::{
+    self, PublishConfig, PublishMessage, PublishedMessage, QosPreset, TopicBridgeConfig,
+    TopicBridgeStatus, TopicMessage,
+};
+use arci_ros2::Node;
+use chrono::Utc;
+use futures::stream::StreamExt;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::broadcast;
+
+// messages kept for slow WebSocket clients before they lag
+const CHANNEL_CAPACITY: usize = 64;
+
+#[derive(Default)]
+struct Received {
+    latest: Option<TopicMessage>,
+    count: u64,
+}
+
+struct Shared {
+    received: Mutex<Received>,
+    sender: broadcast::Sender<TopicMessage>,
+}
+
+/// Subscription to a ROS topic of any supported type, keeping the latest
+/// message as JSON and forwarding the messages to WebSocket clients.
+pub struct TopicBridge {
+    config: TopicBridgeConfig,
+    shared: Arc<Shared>,
+}
+
+impl TopicBridge {
+    /// Subscribes to the topic of `config`. The subscription ends when the
+    /// bridge is dropped.
+    pub fn subscribe(node: &Node, config: &TopicBridgeConfig) -> Result<TopicBridge, Error> {
+        let throttle = config.throttle()?;
+        let mut subscriber = node.r2r().subscribe_untyped(
+            &config.topic,
+            &config.msg_type,
+            config.qos.to_profile(),
+        )?;
+
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        let shared = Arc::new(Shared {
+            received: Mutex::new(Received::default()),
+            sender,
+        });
+        let weak_shared = Arc::downgrade(&shared);
+        let topic = config.topic.clone();
+        let msg_type = config.msg_type.clone();
+        tokio::spawn(async move {
+            let mut last_sent: Option<Instant> = None;
+            while let Some(msg) = subscriber.next().await {
+                let Some(shared) = weak_shared.upgrade() else {
+                    break;
+                };
+                let msg = match msg {
+                    Ok(msg) => msg,
+                    Err(e) => {
+                        log::warn!("failed to convert a message of {}: {:?}", topic, e);
+                        continue;
+                    }
+                };
+                let msg = TopicMessage {
+                    topic: topic.clone(),
+                    msg_type: msg_type.clone(),
+                    received_at: Utc::now(),
+                    msg,
+                };
+                {
+                    let mut received = shared.received.lock().unwrap();
+                    received.latest = Some(msg.clone());
+                    received.count += 1;
+                }
+                if is_throttled(last_sent, throttle) {
+                    continue;
+                }
+                last_sent = Some(Instant::now());
+                // no WebSocket client is connected if this fails
+                let _ = shared.sender.send(msg);
+            }
+            log::info!("subscription to {} ended", topic);
+        });
+
+        log::info!(
+            "bridging {} ({}) as {}",
+            config.topic,
+            config.msg_type,
+            config.name
+        );
+        Ok(TopicBridge {
+            config: config.clone(),
+            shared,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    pub fn latest(&self) -> Result<TopicMessage, Error> {
+        self.shared
+            .received
+            .lock()
+            .unwrap()
+            .latest
+            .clone()
+            .ok_or_else(|| Error::NoTopicMessage(self.config.topic.clone()))
+    }
+
+    /// Returns a receiver of the messages received from now on.
+    pub fn subscribe_messages(&self) -> broadcast::Receiver<TopicMessage> {
+        self.shared.sender.subscribe()
+    }
+
+    pub fn status(&self) -> TopicBridgeStatus {
+        let received = self.shared.received.lock().unwrap();
+        TopicBridgeStatus {
+            name: self.config.name.clone(),
+            topic: self.config.topic.clone(),
+            msg_type: self.config.msg_type.clone(),
+            qos: self.config.qos,
+            throttle: self.config.throttle,
+            received: received.count,
+            last_received_at: received.latest.as_ref().map(|msg| msg.received_at),
+        }
+    }
+}
+
+/// Publishers of the topics of the allow-list, created on the first message
+/// of each type and QoS.
+pub struct TopicPublishers {
+    node: Node,
+    allowed: Vec<PublishConfig>,
+    publishers: Mutex<HashMap<(String, String, QosPreset), r2r::PublisherUntyped>>,
+}
+
+impl TopicPublishers {
+    pub fn new(node: Node, allowed: &[PublishConfig]) -> TopicPublishers {
+        TopicPublishers {
+            node,
+            allowed: allowed.to_vec(),
+            publishers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Publishes `request.msg` on the topic `name` of the allow-list.
+    pub fn publish(&self, name: &str, request: PublishMessage) -> Result<PublishedMessage, Error> {
+        let allowed = find_allowed(&self.allowed, name, &request.msg_type)?;
+
+        let key = (allowed.topic.clone(), request.msg_type.clone(), request.qos);
+        let mut publishers = self.publishers.lock().unwrap();
+        let publisher = match publishers.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                log::info!(
+                    "creating publisher of {} ({}, {:?})",
+                    allowed.topic,
+                    request.msg_type,
+                    request.qos
+                );
+                let publisher = self.node.r2r().create_publisher_untyped(
+                    &allowed.topic,
+                    &request.msg_type,
+                    request.qos.to_profile(),
+                )?;
+                entry.insert(publisher)
+            }
+        };
+        publisher
+            .publish(request.msg)
+            .map_err(Error::from_untyped)?;
+
+        Ok(PublishedMessage {
+            topic: allowed.topic.clone(),
+            msg_type: request.msg_type,
+            qos: request.qos,
+            published_at: Utc::now(),
+        })
+    }
+}
+
+/// Returns the entry of the allow-list letting clients publish `msg_type` on
+/// the topic `name`.
+fn find_allowed<'a>(
+    allowed: &'a [PublishConfig],
+    name: &str,
+    msg_type: &str,
+) -> Result<&'a PublishConfig, Error> {
+    let allowed = allowed
+        .iter()
+        .find(|allowed| allowed.matches(name) && allowed.allows(msg_type))
+        .ok_or_else(|| Error::TopicNotAllowed {
+            topic: name.to_string(),
+            msg_type: msg_type.to_string(),
+        })?;
+    if !topic::is_supported_type(msg_type) {
+        return Err(Error::InvalidMessage(format!(
+            "type {} is not supported",
+            msg_type
+        )));
+    }
+    Ok(allowed)
+}
+
+fn is_throttled(last_sent: Option<Instant>, throttle: Option<Duration>) -> bool {
+    match (last_sent, throttle) {
+        (Some(last_sent), Some(throttle)) => last_sent.elapsed() < throttle,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_message_is_not_throttled() {
+        assert!(!is_throttled(None, Some(Duration::from_secs(1))));
+        assert!(!is_throttled(None, None));
+    }
+
+    #[test]
+    fn messages_within_throttle_are_skipped() {
+        let last_sent = Some(Instant::now());
+        assert!(is_throttled(last_sent, Some(Duration::from_secs(60))));
+        // every message is sent without throttle
+        assert!(!is_throttled(last_sent, None));
+        assert!(!is_throttled(last_sent, Some(Duration::ZERO)));
+
+        let last_sent = Instant::now().checked_sub(Duration::from_millis(200));
+        assert!(!is_throttled(last_sent, Some(Duration::from_millis(100))));
+    }
+
+    fn allow_list() -> Vec<PublishConfig> {
+        vec![
+            PublishConfig {
+                topic: "/cmd_vel".to_string(),
+                types: vec!["geometry_msgs/msg/Twist".to_string()],
+            },
+            PublishConfig {
+                topic: "chatter".to_string(),
+                types: vec![],
+            },
+        ]
+    }
+
+    #[test]
+    fn publishing_follows_the_allow_list() {
+        let allowed = allow_list();
+        let twist = "geometry_msgs/msg/Twist";
+        assert_eq!(
+            find_allowed(&allowed, "cmd_vel", twist).unwrap().topic,
+            "/cmd_vel"
+        );
+        assert_eq!(
+            find_allowed(&allowed, "/chatter", "std_msgs/msg/String")
+                .unwrap()
+                .topic,
+            "chatter"
+        );
+
+        assert!(matches!(
+            find_allowed(&allowed, "odom", twist),
+            Err(Error::TopicNotAllowed { topic, .. }) if topic == "odom"
+        ));
+        assert!(matches!(
+            find_allowed(&allowed, "cmd_vel", "std_msgs/msg/String"),
+            Err(Error::TopicNotAllowed { msg_type, .. }) if msg_type == "std_msgs/msg/String"
+        ));
+        // any type is allowed on chatter, but only the supported ones exist
+        assert!(matches!(
+            find_allowed(&allowed, "chatter", "my_msgs/msg/Custom"),
+            Err(Error::InvalidMessage(_))
+        ));
+    }
+}
@@ -0,0 +1,264 @@
+use crate::config::{Config, ConfigError, CONFIG_ENV};
+use crate::error::Error;
+use crate::gateway::Gateway;
+use crate::models::controller::ExecuteTrajectory;
+use crate::models::execution::ExecutionStatus;
+use crate::trajectory::Submission;
+use clap::{Args, Parser, Subcommand};
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
+use uuid::Uuid;
+
+/// Everything after this argument is left to the ROS client library.
+pub const ROS_ARGS: &str = "--ros-args";
+
+/// REST gateway to ROS 2 topics and FollowJointTrajectory controllers.
+#[derive(Debug, Parser)]
+#[command(version, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    // arguments of `serve` when no subcommand is given, so `ros2 run`
+    // starts the server
+    #[command(flatten)]
+    serve: ServeArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the HTTP server
+    Serve(ServeArgs),
+    /// Validate the config and exit
+    CheckConfig(ConfigArgs),
+    /// Run a trajectory read from a JSON or YAML file and wait for its result
+    SendTrajectory(SendTrajectoryArgs),
+    /// Cancel a goal sent by `send-trajectory`
+    Cancel(CancelArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ConfigArgs {
+    /// TOML or YAML config file
+    #[arg(long, env = CONFIG_ENV)]
+    pub config: Option<PathBuf>,
+    /// overrides `node.namespace`
+    #[arg(long)]
+    pub namespace: Option<String>,
+    /// overrides `node.name`
+    #[arg(long)]
+    pub node_name: Option<String>,
+}
+
+impl ConfigArgs {
+    /// Loads the config file, or the defaults, and applies the overrides.
+    pub fn load(&self) -> Result<Config, ConfigError> {
+        let mut config = match &self.config {
+            Some(path) => Config::load(path)?,
+            None => Config::default(),
+        };
+        if let Some(namespace) = &self.namespace {
+            config.node.namespace = namespace.clone();
+        }
+        if let Some(node_name) = &self.node_name {
+            config.node.name = node_name.clone();
+        }
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+    /// overrides `server.bind`
+    #[arg(long)]
+    pub bind: Option<SocketAddr>,
+}
+
+impl ServeArgs {
+    pub fn load(&self) -> Result<Config, ConfigError> {
+        let mut config = self.config.load()?;
+        if let Some(bind) = self.bind {
+            config.server.bind = bind;
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct SendTrajectoryArgs {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+    /// body of `POST /controllers/:name/execute`, as JSON or YAML
+    pub file: PathBuf,
+    /// the first configured controller if omitted
+    #[arg(long)]
+    pub controller: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct CancelArgs {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+    /// goal UUID printed by `send-trajectory`
+    pub goal_id: Uuid,
+    /// the first configured controller if omitted
+    #[arg(long)]
+    pub controller: Option<String>,
+}
+
+impl Cli {
+    /// Parses the process arguments up to `--ros-args`. The ROS arguments
+    /// stay in the process arguments, where the ROS context reads them.
+    pub fn parse_args() -> Cli {
+        Cli::parse_from(std::env::args().take_while(|arg| arg != ROS_ARGS))
+    }
+
+    pub fn command(self) -> Command {
+        self.command.unwrap_or(Command::Serve(self.serve))
+    }
+}
+
+pub fn check_config(args: &ConfigArgs) -> ExitCode {
+    match args.load() {
+        Ok(_) => {
+            match &args.config {
+                Some(path) => println!("{} is valid", path.display()),
+                None => println!("no config given, the defaults are valid"),
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Resolves on SIGINT or SIGTERM.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            log::error!("failed to listen for SIGINT: {:?}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                log::error!("failed to listen for SIGTERM: {:?}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => log::warn!("received SIGINT"),
+        _ = terminate => log::warn!("received SIGTERM"),
+    }
+}
+
+/// Sends the trajectory of `args.file` and waits for its result. Succeeds
+/// only if the goal succeeded. The goal is canceled on SIGINT or SIGTERM.
+pub async fn send_trajectory(
+    gateway: &Gateway,
+    args: &SendTrajectoryArgs,
+    shutdown_timeout: Duration,
+) -> ExitCode {
+    let goal = match read_trajectory(&args.file) {
+        Ok(goal) => goal,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let submission = gateway.execute_follow_joint_trajectory(
+        args.controller.as_deref(),
+        goal.trajectory,
+        goal.complete_condition,
+        &goal.timeouts,
+    );
+    let acceptance = match submission {
+        Ok(Submission::Sent(acceptance)) => acceptance,
+        // nothing else runs in this process
+        Ok(Submission::Queued(_)) => unreachable!("the executor of a new gateway is idle"),
+        Err(e) => {
+            eprintln!("failed to send the trajectory: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let goal_id = match acceptance.await {
+        Ok(Ok(goal_id)) => goal_id,
+        Ok(Err(e)) => {
+            eprintln!("goal was not accepted: {}", e);
+            return ExitCode::FAILURE;
+        }
+        Err(_) => {
+            eprintln!("goal request was dropped");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("goal {} accepted", goal_id);
+
+    let status = tokio::select! {
+        status = gateway.wait_follow_joint_trajectory(&goal_id) => status,
+        _ = shutdown_signal() => {
+            gateway.shutdown(shutdown_timeout).await;
+            return ExitCode::FAILURE;
+        }
+    };
+    let execution = gateway.get_follow_joint_trajectory(&goal_id);
+    let error_string = execution
+        .and_then(|execution| execution.error_string)
+        .unwrap_or_default();
+    println!("goal {} finished: {:?} {}", goal_id, status, error_string);
+    if status == ExecutionStatus::Succeeded {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+pub async fn cancel(gateway: &Gateway, args: &CancelArgs) -> ExitCode {
+    let res =
+        gateway.cancel_follow_joint_trajectory_on_server(args.controller.as_deref(), args.goal_id);
+    let res = match res {
+        Ok(handler) => handler
+            .await
+            .unwrap_or_else(|e| Err(Error::Other(e.into()))),
+        Err(e) => Err(e),
+    };
+    match res {
+        Ok(()) => {
+            println!("canceling goal {}", args.goal_id);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("failed to cancel: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Reads YAML, or JSON for files ending with `.json`.
+fn read_trajectory(path: &Path) -> Result<ExecuteTrajectory, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let res = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    } else {
+        serde_yaml::from_str(&text).map_err(|e| e.to_string())
+    };
+    res.map_err(|e| format!("failed to parse {}: {}", path.display(), e))
+}
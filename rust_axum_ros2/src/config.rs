@@ -0,0 +1,577 @@
+use crate::models::action::ActionConfig;
+use crate::models::controller::ControllerConfig;
+use crate::models::queue::GoalPolicy;
+use crate::models::robot::{LoadRobotModel, UrdfSource};
+use crate::models::service::ServiceTimeouts;
+use crate::models::timeout::ExecutionTimeouts;
+use crate::models::topic::{self, PublishConfig, TopicBridgeConfig};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use thiserror::Error;
+
+/// Environment variable read by the CLI when no `--config` argument is given.
+pub const CONFIG_ENV: &str = "RUST_AXUM_ROS2_CONFIG";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read config {}: {}", path.display(), source)]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse config {}: {}", path.display(), message)]
+    Parse { path: PathBuf, message: String },
+    #[error("invalid config:\n  - {}", .0.join("\n  - "))]
+    Invalid(Vec<String>),
+}
+
+/// Settings of the gateway binary, read from a TOML or YAML file.
+///
+/// Every section is optional and falls back to the values the server used
+/// before the file existed.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub node: NodeConfig,
+    pub topics: TopicConfig,
+    pub executor: ExecutorConfig,
+    pub controllers: Vec<ControllerConfig>,
+    /// action servers driven with JSON goals over `/actions/:name/goals`
+    pub actions: Vec<ActionConfig>,
+    /// topics forwarded to HTTP and WebSocket clients
+    pub bridges: Vec<TopicBridgeConfig>,
+    /// topics clients may publish to; publishing is disabled if empty
+    pub publish: Vec<PublishConfig>,
+    /// default timeouts of `POST /services/:name/call`
+    pub services: ServiceTimeouts,
+    /// loaded at startup to validate trajectories against the joint limits;
+    /// none if omitted
+    pub robot_model: Option<LoadRobotModel>,
+    pub storage: StorageConfig,
+    pub logger: LoggerConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            server: ServerConfig::default(),
+            node: NodeConfig::default(),
+            topics: TopicConfig::default(),
+            executor: ExecutorConfig::default(),
+            controllers: vec![ControllerConfig::default()],
+            actions: vec![],
+            bridges: vec![],
+            publish: vec![],
+            services: ServiceTimeouts::default(),
+            robot_model: None,
+            storage: StorageConfig::default(),
+            logger: LoggerConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub bind: SocketAddr,
+    /// capacity of the channel between the HTTP handlers and the gateway
+    pub command_channel_capacity: usize,
+    /// on SIGINT or SIGTERM, how long to wait for the cancel responses of the
+    /// running goals, then for the open connections, in seconds
+    pub shutdown_timeout: f64,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([0, 0, 0, 0], 3000)),
+            command_channel_capacity: 2,
+            shutdown_timeout: 5.0,
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn shutdown_timeout(&self) -> Option<Duration> {
+        Duration::try_from_secs_f64(self.shutdown_timeout).ok()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NodeConfig {
+    pub name: String,
+    pub namespace: String,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            name: "rust_axum_ros2_node".to_string(),
+            namespace: "".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TopicConfig {
+    pub user: String,
+    pub task: String,
+    pub joint_states: String,
+}
+
+impl Default for TopicConfig {
+    fn default() -> Self {
+        Self {
+            user: "user".to_string(),
+            task: "task".to_string(),
+            joint_states: "/joint_states".to_string(),
+        }
+    }
+}
+
+/// Initial settings of every controller, changeable at runtime over REST.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExecutorConfig {
+    pub goal_policy: GoalPolicy,
+    pub timeouts: ExecutionTimeouts,
+}
+
+/// Where users and tasks are kept.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(tag = "backend", rename_all = "snake_case", deny_unknown_fields)]
+pub enum StorageConfig {
+    /// lost when the process exits
+    #[default]
+    Memory,
+    Sqlite {
+        path: PathBuf,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggerConfig {
+    pub stdout_level: log::LevelFilter,
+    pub file_level: log::LevelFilter,
+    /// file logging is disabled if omitted
+    pub file: Option<PathBuf>,
+}
+
+impl Default for LoggerConfig {
+    fn default() -> Self {
+        Self {
+            stdout_level: log::LevelFilter::Trace,
+            file_level: log::LevelFilter::Info,
+            file: Some(PathBuf::from("log/output.log")),
+        }
+    }
+}
+
+impl Config {
+    /// Reads and validates the config at `path`. Files ending with `.yaml`
+    /// or `.yml` are read as YAML, the others as TOML.
+    pub fn load(path: &Path) -> Result<Config, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let is_yaml = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("yaml" | "yml")
+        );
+        let config: Config = if is_yaml {
+            serde_yaml::from_str(&text).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(&text).map_err(|e| e.to_string())
+        }
+        .map_err(|message| ConfigError::Parse {
+            path: path.to_path_buf(),
+            message,
+        })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Returns every problem of the config at once.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = vec![];
+
+        if self.server.command_channel_capacity == 0 {
+            problems.push("server.command_channel_capacity must be greater than 0".to_string());
+        }
+        if self.server.shutdown_timeout().is_none() {
+            problems.push(format!(
+                "server.shutdown_timeout {} must be a non-negative number of seconds",
+                self.server.shutdown_timeout
+            ));
+        }
+        if !is_ros_name(&self.node.name) {
+            problems.push(format!(
+                "node.name {:?} must be non-empty, start with a letter and contain only \
+                 alphanumerics and underscores",
+                self.node.name
+            ));
+        }
+        if !self.node.namespace.is_empty() && !self.node.namespace.starts_with('/') {
+            problems.push(format!(
+                "node.namespace {:?} must be empty or start with '/'",
+                self.node.namespace
+            ));
+        }
+        for (key, topic) in [
+            ("topics.user", &self.topics.user),
+            ("topics.task", &self.topics.task),
+            ("topics.joint_states", &self.topics.joint_states),
+        ] {
+            if topic.is_empty() {
+                problems.push(format!("{} must not be empty", key));
+            }
+        }
+
+        let timeouts = &self.executor.timeouts;
+        for e in [
+            timeouts.availability().err(),
+            timeouts.feedback_silence().err(),
+            timeouts.duration_margin().err(),
+            timeouts.poll_interval().err(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            problems.push(format!("executor.timeouts: {}", e));
+        }
+
+        for e in [
+            self.services.availability().err(),
+            self.services.response().err(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            problems.push(format!("services: {}", e));
+        }
+
+        if self.controllers.is_empty() {
+            problems.push("at least one controller must be configured".to_string());
+        }
+        let mut names = HashSet::new();
+        for controller in &self.controllers {
+            if controller.name.is_empty() {
+                problems.push("controllers: name must not be empty".to_string());
+            } else if !names.insert(&controller.name) {
+                problems.push(format!(
+                    "controllers: name {:?} is used twice",
+                    controller.name
+                ));
+            }
+            if controller.action_name.is_empty() {
+                problems.push(format!(
+                    "controllers.{}: action_name must not be empty",
+                    controller.name
+                ));
+            }
+        }
+        // goals of controllers and actions share the execution registry
+        for action in &self.actions {
+            if action.name.is_empty() {
+                problems.push("actions: name must not be empty".to_string());
+            } else if !names.insert(&action.name) {
+                problems.push(format!(
+                    "actions: name {:?} is used twice or by a controller",
+                    action.name
+                ));
+            }
+            if action.action_name.is_empty() {
+                problems.push(format!(
+                    "actions.{}: action_name must not be empty",
+                    action.name
+                ));
+            }
+            if !topic::is_supported_interface(&action.action_type, "action") {
+                problems.push(format!(
+                    "actions.{}: type {:?} must be <package>/action/<Name> of one of {}",
+                    action.name,
+                    action.action_type,
+                    topic::SUPPORTED_PACKAGES.join(", ")
+                ));
+            }
+            let timeouts = &action.timeouts;
+            for e in [
+                timeouts.availability().err(),
+                timeouts.feedback_silence().err(),
+                timeouts.duration().err(),
+                timeouts.poll_interval().err(),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                problems.push(format!("actions.{}.timeouts: {}", action.name, e));
+            }
+        }
+
+        let mut names = HashSet::new();
+        for bridge in &self.bridges {
+            if bridge.name.is_empty() {
+                problems.push("bridges: name must not be empty".to_string());
+            } else if !names.insert(&bridge.name) {
+                problems.push(format!("bridges: name {:?} is used twice", bridge.name));
+            }
+            if bridge.topic.is_empty() {
+                problems.push(format!("bridges.{}: topic must not be empty", bridge.name));
+            }
+            if !topic::is_supported_type(&bridge.msg_type) {
+                problems.push(format!(
+                    "bridges.{}: type {:?} must be <package>/msg/<Name> of one of {}",
+                    bridge.name,
+                    bridge.msg_type,
+                    topic::SUPPORTED_PACKAGES.join(", ")
+                ));
+            }
+            if let Err(e) = bridge.throttle() {
+                problems.push(format!("bridges.{}: {}", bridge.name, e));
+            }
+        }
+
+        let mut topics = HashSet::new();
+        for publish in &self.publish {
+            if publish.topic.is_empty() {
+                problems.push("publish: topic must not be empty".to_string());
+            } else if !topics.insert(publish.topic.trim_start_matches('/')) {
+                problems.push(format!("publish: topic {:?} is used twice", publish.topic));
+            }
+            for msg_type in &publish.types {
+                if !topic::is_supported_type(msg_type) {
+                    problems.push(format!(
+                        "publish.{}: type {:?} must be <package>/msg/<Name> of one of {}",
+                        publish.topic,
+                        msg_type,
+                        topic::SUPPORTED_PACKAGES.join(", ")
+                    ));
+                }
+            }
+        }
+
+        if let Some(robot_model) = &self.robot_model {
+            match &robot_model.source {
+                UrdfSource::File { path } if path.is_empty() => {
+                    problems.push("robot_model.source.path must not be empty".to_string());
+                }
+                UrdfSource::Parameter { name } if name.is_empty() => {
+                    problems.push("robot_model.source.name must not be empty".to_string());
+                }
+                UrdfSource::Topic { topic, timeout_sec } => {
+                    if topic.is_empty() {
+                        problems.push("robot_model.source.topic must not be empty".to_string());
+                    }
+                    if Duration::try_from_secs_f64(*timeout_sec).is_err() {
+                        problems.push(format!(
+                            "robot_model.source.timeout_sec {} must be a non-negative number \
+                             of seconds",
+                            timeout_sec
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let StorageConfig::Sqlite { path } = &self.storage {
+            if path.as_os_str().is_empty() {
+                problems.push("storage.path must not be empty".to_string());
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+}
+
+fn is_ros_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(config: &Config) -> Vec<String> {
+        match config.validate() {
+            Ok(()) => vec![],
+            Err(ConfigError::Invalid(problems)) => problems,
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn example_is_default() {
+        let config: Config = toml::from_str(include_str!("../config/example.toml")).unwrap();
+        assert_eq!(config, Config::default());
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn empty_file_is_default() {
+        assert_eq!(toml::from_str::<Config>("").unwrap(), Config::default());
+        assert_eq!(
+            serde_yaml::from_str::<Config>("{}").unwrap(),
+            Config::default()
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(toml::from_str::<Config>("[server]\nport = 3000").is_err());
+        assert!(toml::from_str::<Config>("[storage]\nbackend = \"redis\"").is_err());
+    }
+
+    #[test]
+    fn misspelled_timeouts_are_rejected() {
+        let e =
+            toml::from_str::<Config>("[executor.timeouts]\nfeedback_silense = 10.0").unwrap_err();
+        assert!(e.to_string().contains("feedback_silense"), "{}", e);
+    }
+
+    #[test]
+    fn every_problem_is_reported() {
+        let mut config = Config::default();
+        config.server.command_channel_capacity = 0;
+        config.server.shutdown_timeout = -1.0;
+        config.node.name = "1node".to_string();
+        config.node.namespace = "ns".to_string();
+        config.topics.user = "".to_string();
+        config.executor.timeouts.availability = f64::NAN;
+        config.storage = StorageConfig::Sqlite {
+            path: PathBuf::new(),
+        };
+        let problems = problems(&config);
+        assert_eq!(problems.len(), 7, "{:?}", problems);
+    }
+
+    #[test]
+    fn names_must_be_unique() {
+        let mut config = Config::default();
+        config.controllers.push(ControllerConfig::default());
+        config.actions.push(ActionConfig {
+            name: "default".to_string(),
+            action_name: "gripper_cmd".to_string(),
+            action_type: "control_msgs/action/GripperCommand".to_string(),
+            timeouts: Default::default(),
+        });
+        let problems = problems(&config);
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].contains("used twice"));
+        assert!(problems[1].contains("used twice or by a controller"));
+
+        config.controllers.clear();
+        config.actions.clear();
+        assert_eq!(
+            self::problems(&config),
+            vec!["at least one controller must be configured"]
+        );
+    }
+
+    #[test]
+    fn action_types_are_checked() {
+        let mut config = Config::default();
+        config.actions.push(ActionConfig {
+            name: "gripper".to_string(),
+            action_name: "gripper_cmd".to_string(),
+            action_type: "control_msgs/msg/GripperCommand".to_string(),
+            timeouts: Default::default(),
+        });
+        assert_eq!(problems(&config).len(), 1);
+        config.actions[0].action_type = "control_msgs/action/GripperCommand".to_string();
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn bridge_throttle_is_checked() {
+        let mut config = Config::default();
+        config.bridges.push(TopicBridgeConfig {
+            name: "imu".to_string(),
+            topic: "/imu/data".to_string(),
+            msg_type: "sensor_msgs/msg/Imu".to_string(),
+            qos: Default::default(),
+            throttle: Some(0.1),
+        });
+        config.validate().unwrap();
+
+        for throttle in [-0.1, f64::NAN, f64::INFINITY] {
+            config.bridges[0].throttle = Some(throttle);
+            let problems = problems(&config);
+            assert_eq!(problems.len(), 1, "{:?}", problems);
+            assert!(problems[0].starts_with("bridges.imu: "), "{:?}", problems);
+        }
+    }
+
+    #[test]
+    fn robot_model_is_checked() {
+        let config: Config = toml::from_str(
+            "[robot_model]\nsource = { type = \"topic\", timeout_sec = -1.0 }\n\
+             joint_names = [\"joint1\"]",
+        )
+        .unwrap();
+        let robot_model = config.robot_model.as_ref().unwrap();
+        assert_eq!(
+            robot_model.source,
+            UrdfSource::Topic {
+                topic: "/robot_description".to_string(),
+                timeout_sec: -1.0,
+            }
+        );
+        assert_eq!(robot_model.joint_names, Some(vec!["joint1".to_string()]));
+        let problems = problems(&config);
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+        assert!(problems[0].starts_with("robot_model.source.timeout_sec"));
+
+        assert!(toml::from_str::<Config>(
+            "[robot_model]\nsource = { type = \"file\", path = \"a.urdf\", name = \"b\" }"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn load_reads_toml_and_yaml() {
+        let dir =
+            std::env::temp_dir().join(format!("rust_axum_ros2_config_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let toml_path = dir.join("config.toml");
+        std::fs::write(&toml_path, "[node]\nname = \"gateway\"\n").unwrap();
+        assert_eq!(Config::load(&toml_path).unwrap().node.name, "gateway");
+
+        let yaml_path = dir.join("config.yaml");
+        std::fs::write(&yaml_path, "node:\n  name: gateway\n").unwrap();
+        assert_eq!(Config::load(&yaml_path).unwrap().node.name, "gateway");
+
+        std::fs::write(&yaml_path, "node:\n  name: \"\"\n").unwrap();
+        assert!(matches!(
+            Config::load(&yaml_path),
+            Err(ConfigError::Invalid(_))
+        ));
+        assert!(matches!(
+            Config::load(&dir.join("missing.toml")),
+            Err(ConfigError::Read { .. })
+        ));
+        std::fs::write(&toml_path, "[node\n").unwrap();
+        assert!(matches!(
+            Config::load(&toml_path),
+            Err(ConfigError::Parse { .. })
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
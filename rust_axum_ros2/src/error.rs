@@ -1,10 +1,242 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Value};
+use std::time::Duration;
 use thiserror::Error;
+use uuid::Uuid;
 
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum Error {
-    #[error("rust_axum_ros2: No joint_state is available")]
+    #[error("rust_axum_ros2: No valid goal exists")]
     NoValidGoalExists,
+    #[error("rust_axum_ros2: Goal {} is not found", .0)]
+    GoalNotFound(Uuid),
+    #[error("rust_axum_ros2: Goal {} has already finished", .0)]
+    GoalAlreadyFinished(Uuid),
+    #[error("rust_axum_ros2: Goal {} is already being canceled", .0)]
+    GoalCanceling(Uuid),
+    #[error("rust_axum_ros2: Controller {} is not found", .0)]
+    ControllerNotFound(String),
+    #[error("rust_axum_ros2: Action {} is not found", .0)]
+    ActionNotFound(String),
+    #[error("rust_axum_ros2: Another goal is running")]
+    Busy,
+    #[error("rust_axum_ros2: Goal {} was removed from the queue", .0)]
+    Dequeued(Uuid),
+    #[error(
+        "rust_axum_ros2: Queue mismatch: queued = {:?}, requested = {:?}",
+        queued,
+        requested
+    )]
+    QueueMismatch {
+        queued: Vec<Uuid>,
+        requested: Vec<Uuid>,
+    },
+    #[error("rust_axum_ros2: Action server is not available after {:?}", .0)]
+    ActionServerUnavailable(Duration),
+    #[error("rust_axum_ros2: Goal was not accepted within {:?}", .0)]
+    GoalNotAccepted(Duration),
+    #[error("rust_axum_ros2: Invalid timeout {} = {}", name, value)]
+    InvalidTimeout { name: &'static str, value: f64 },
+    #[error("rust_axum_ros2: Goal rejected: {}", .0)]
+    GoalRejected(String),
+    #[error("rust_axum_ros2: No joint_state is available")]
+    NoJointStateAvailable,
+    #[error("rust_axum_ros2: Joint {} is not found", .0)]
+    JointNotFound(String),
+    #[error("rust_axum_ros2: Joint state of {} is stale", .0)]
+    StaleJointState(String),
+    #[error("rust_axum_ros2: Robot description is not available: {}", .0)]
+    RobotDescriptionUnavailable(String),
+    #[error("rust_axum_ros2: URDF: {}", .0)]
+    Urdf(#[from] urdf_rs::UrdfError),
+    #[error("rust_axum_ros2: {} {} is not found", kind, id)]
+    RecordNotFound { kind: &'static str, id: u64 },
+    #[error("rust_axum_ros2: Invalid record: {}", .0)]
+    InvalidRecord(String),
+    #[error("rust_axum_ros2: Storage: {}", .0)]
+    Storage(#[from] rusqlite::Error),
+    #[error("rust_axum_ros2: Invalid message: {}", .0)]
+    InvalidMessage(String),
+    #[error("rust_axum_ros2: Topic {} is not found", .0)]
+    TopicNotFound(String),
+    #[error("rust_axum_ros2: No message was received on {}", .0)]
+    NoTopicMessage(String),
+    #[error("rust_axum_ros2: Publishing {} on {} is not allowed", msg_type, topic)]
+    TopicNotAllowed { topic: String, msg_type: String },
+    #[error("rust_axum_ros2: URDF file {} is not the one of the config", .0)]
+    UrdfFileNotAllowed(String),
+    #[error("rust_axum_ros2: Service {} is not available after {:?}", .0, .1)]
+    ServiceUnavailable(String, Duration),
+    #[error("rust_axum_ros2: Service {} did not respond within {:?}", .0, .1)]
+    ServiceTimeout(String, Duration),
+    #[error("rust_axum_ros2: Gateway is not running")]
+    GatewayUnavailable,
+    #[error("rust_axum_ros2: ROS: {}", .0)]
+    Ros(#[from] r2r::Error),
+    #[error("rust_axum_ros2: Invalid trajectory: {}", .0)]
+    InvalidTrajectory(arci::Error),
+    #[error("rust_axum_ros2: {}", .0)]
+    Arci(#[from] arci::Error),
     #[error("rust_axum_ros2: Other: {:?}", .0)]
     Other(#[from] anyhow::Error),
 }
+
+impl Error {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Error::NoValidGoalExists
+            | Error::GoalNotFound(_)
+            | Error::JointNotFound(_)
+            | Error::ControllerNotFound(_)
+            | Error::ActionNotFound(_)
+            | Error::RecordNotFound { .. }
+            | Error::TopicNotFound(_) => StatusCode::NOT_FOUND,
+            Error::GoalAlreadyFinished(_)
+            | Error::GoalCanceling(_)
+            | Error::Busy
+            | Error::Dequeued(_) => StatusCode::CONFLICT,
+            Error::QueueMismatch { .. }
+            | Error::InvalidTrajectory(_)
+            | Error::InvalidRecord(_)
+            | Error::InvalidMessage(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::TopicNotAllowed { .. } | Error::UrdfFileNotAllowed(_) => StatusCode::FORBIDDEN,
+            Error::Urdf(_) | Error::Arci(arci::Error::NoJoint(_)) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            Error::ActionServerUnavailable(_)
+            | Error::RobotDescriptionUnavailable(_)
+            | Error::NoJointStateAvailable
+            | Error::StaleJointState(_)
+            | Error::NoTopicMessage(_)
+            | Error::ServiceUnavailable(..)
+            | Error::GatewayUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            Error::ServiceTimeout(..) | Error::GoalNotAccepted(_) => StatusCode::GATEWAY_TIMEOUT,
+            Error::GoalRejected(_) | Error::InvalidTimeout { .. } => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Converts an error of the untyped r2r interfaces, reporting JSON that
+    /// does not match the interface type as `InvalidMessage`.
+    pub fn from_untyped(e: r2r::Error) -> Error {
+        match e {
+            r2r::Error::SerdeError { err } => Error::InvalidMessage(err),
+            e => Error::Ros(e),
+        }
+    }
+
+    /// Machine-readable details of the error, added to HTTP error responses.
+    pub fn detail(&self) -> Option<Value> {
+        match self {
+            Error::InvalidTrajectory(e) => Some(arci_error_detail(e)),
+            _ => None,
+        }
+    }
+}
+
+/// Replies `{"message": ..., "error": ...}` with the status of the error.
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let body = match self.detail() {
+            Some(detail) => json!({ "message": self.to_string(), "error": detail }),
+            None => json!({ "message": self.to_string() }),
+        };
+        (self.status_code(), Json(body)).into_response()
+    }
+}
+
+fn arci_error_detail(e: &arci::Error) -> Value {
+    match e {
+        arci::Error::OutOfLimit {
+            name,
+            position,
+            limit,
+        } => json!({
+            "type": "out_of_limit",
+            "name": name,
+            "position": position,
+            "lower": limit.start(),
+            "upper": limit.end(),
+        }),
+        arci::Error::VelocityOutOfLimit {
+            name,
+            velocity,
+            limit,
+        } => json!({
+            "type": "velocity_out_of_limit",
+            "name": name,
+            "velocity": velocity,
+            "limit": limit,
+        }),
+        arci::Error::LengthMismatch { model, input } => json!({
+            "type": "length_mismatch",
+            "model": model,
+            "input": input,
+        }),
+        arci::Error::JointNamesMismatch { partial, full } => json!({
+            "type": "joint_names_mismatch",
+            "partial": partial,
+            "full": full,
+        }),
+        arci::Error::NoJoint(name) => json!({
+            "type": "no_joint",
+            "name": name,
+        }),
+        arci::Error::InterpolationError(message) => json!({
+            "type": "interpolation_error",
+            "message": message,
+        }),
+        arci::Error::InvalidTimeFromStart {
+            index,
+            time_from_start,
+            previous,
+        } => json!({
+            "type": "invalid_time_from_start",
+            "index": index,
+            "time_from_start": time_from_start,
+            "previous": previous,
+        }),
+        arci::Error::TimeFromStartOutOfRange {
+            time_from_start,
+            max,
+        } => json!({
+            "type": "time_from_start_out_of_range",
+            "time_from_start": time_from_start,
+            "max": max,
+        }),
+        _ => json!({ "type": "other" }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_errors_have_their_status() {
+        let wait = Duration::from_secs(1);
+        assert_eq!(
+            Error::ServiceUnavailable("/add_two_ints".to_string(), wait).status_code(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            Error::ServiceTimeout("/add_two_ints".to_string(), wait).status_code(),
+            StatusCode::GATEWAY_TIMEOUT
+        );
+    }
+
+    #[test]
+    fn mismatched_json_is_invalid_message() {
+        let e = Error::from_untyped(r2r::Error::SerdeError {
+            err: "missing field `a`".to_string(),
+        });
+        assert!(matches!(&e, Error::InvalidMessage(err) if err == "missing field `a`"));
+        assert_eq!(e.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(e.detail(), None);
+    }
+}
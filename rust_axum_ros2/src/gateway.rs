@@ -1,57 +1,1040 @@
+use crate::action::{GoalAcceptance, JsonActionExecutor};
+use crate::bridge::{TopicBridge, TopicPublishers};
+use crate::config::Config;
 use crate::error::Error;
+use crate::interpolation;
+use crate::models::action::{ActionStatus, SendActionGoal};
+use crate::models::controller::{
+    CombinedExecution, ControllerStatus, ExecuteControllers, ExecuteMergedTrajectory,
+    ExecuteTrajectory, SynchronizedExecution,
+};
+use crate::models::execution::{Execution, ExecutionEvent, ExecutionStatus, GoalEvent};
+use crate::models::joint_state::JointState;
+use crate::models::motion::MoveJoints;
+use crate::models::page::{Page, Pagination};
+use crate::models::queue::{GoalPolicy, QueuedGoal};
+use crate::models::robot::{RobotModel, UrdfSource};
+use crate::models::service::{CallService, ServiceResponse};
 use crate::models::task::Task;
+use crate::models::timeout::{ExecutionTimeouts, TimeoutOverrides};
+use crate::models::topic::{PublishMessage, PublishedMessage, TopicBridgeStatus, TopicMessage};
+use crate::models::trajectory::{CompleteCondition, JointLimits, JointTrajectory};
 use crate::models::user::User;
-use crate::trajectory::FollowJointTrajectoryActionExecutor;
-use arci_ros2::Node;
+use crate::registry::ExecutionRegistry;
+use crate::service::ServiceClients;
+use crate::store::{Record, Store};
+use crate::trajectory::{FollowJointTrajectoryActionExecutor, Submission};
+use crate::urdf;
+use arci_ros2::{JointStateCache, Node};
+use futures::stream::{FuturesUnordered, StreamExt};
 use r2r::{std_msgs, QosProfile};
-use std::time::Duration;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::broadcast;
+use tokio::time::timeout;
+use uuid::Uuid;
+
+// joint states older than this are reported as stale
+const JOINT_STATE_STALE_AFTER: Duration = Duration::from_secs(1);
 
 pub struct Gateway {
-    _node: Node,
+    node: Node,
     user_pub: r2r::Publisher<std_msgs::msg::String>,
     task_pub: r2r::Publisher<std_msgs::msg::String>,
-    follow_joint_trajectory_action_executor: FollowJointTrajectoryActionExecutor,
+    // in configuration order, the first one handles the requests without a
+    // controller name
+    controllers: Vec<FollowJointTrajectoryActionExecutor>,
+    actions: Vec<JsonActionExecutor>,
+    executions: ExecutionRegistry,
+    robot_model: Arc<Mutex<Option<RobotModel>>>,
+    // the only URDF file clients may load
+    urdf_file: Option<String>,
+    joint_states: JointStateCache,
+    bridges: Vec<TopicBridge>,
+    publishers: TopicPublishers,
+    services: ServiceClients,
+    store: Store,
 }
 
 impl Gateway {
-    pub fn new(name: &str, namespace: &str) -> Result<Gateway, Box<dyn std::error::Error>> {
-        let node = Node::new(name, namespace)?;
+    pub fn new(config: &Config) -> Result<Gateway, Box<dyn std::error::Error>> {
+        config.validate()?;
+
+        let node = Node::new(&config.node.name, &config.node.namespace)?;
         node.run_spin_thread(Duration::from_millis(100));
 
-        let user_pub = node
-            .r2r()
-            .create_publisher::<std_msgs::msg::String>("user", QosProfile::default())?;
+        let user_pub = node.r2r().create_publisher::<std_msgs::msg::String>(
+            &config.topics.user,
+            QosProfile::default(),
+        )?;
+
+        let task_pub = node.r2r().create_publisher::<std_msgs::msg::String>(
+            &config.topics.task,
+            QosProfile::default(),
+        )?;
+
+        let joint_states = JointStateCache::subscribe(&node, &config.topics.joint_states)?;
+
+        let executions = ExecutionRegistry::new();
+        let controllers = config
+            .controllers
+            .iter()
+            .map(|controller| {
+                log::info!("controller {}: {:?}", controller.name, controller);
+                FollowJointTrajectoryActionExecutor::new(
+                    node.clone(),
+                    controller,
+                    config.executor.goal_policy,
+                    config.executor.timeouts,
+                    joint_states.clone(),
+                    executions.clone(),
+                )
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let actions = config
+            .actions
+            .iter()
+            .map(|action| JsonActionExecutor::new(node.clone(), action, executions.clone()))
+            .collect::<Result<_, Error>>()?;
 
-        let task_pub = node
-            .r2r()
-            .create_publisher::<std_msgs::msg::String>("task", QosProfile::default())?;
+        let bridges = config
+            .bridges
+            .iter()
+            .map(|bridge| TopicBridge::subscribe(&node, bridge))
+            .collect::<Result<_, Error>>()?;
 
-        let follow_joint_trajectory_action_executor =
-            FollowJointTrajectoryActionExecutor::new(node.clone(), "follow_joint_trajectory");
+        let publishers = TopicPublishers::new(node.clone(), &config.publish);
+        let services = ServiceClients::new(node.clone(), config.services);
 
         Ok(Gateway {
-            _node: node,
+            node,
             user_pub,
             task_pub,
-            follow_joint_trajectory_action_executor,
+            controllers,
+            actions,
+            executions,
+            robot_model: Arc::new(Mutex::new(None)),
+            urdf_file: match config.robot_model.as_ref().map(|model| &model.source) {
+                Some(UrdfSource::File { path }) => Some(path.clone()),
+                _ => None,
+            },
+            joint_states,
+            bridges,
+            publishers,
+            services,
+            store: Store::open(&config.storage)?,
         })
     }
 
-    pub fn publish_user(&self, user: User) -> r2r::Result<()> {
-        let msg = user.to_msg();
-        self.user_pub.publish(&msg)
+    /// Stores a new user or task with a generated id.
+    pub fn create_record<R: Record>(&self, name: &str) -> Result<R, Error> {
+        self.store.create(name)
+    }
+
+    pub fn find_or_create_record<R: Record>(&self, name: &str) -> Result<R, Error> {
+        self.store.find_or_create(name)
+    }
+
+    pub fn get_record<R: Record>(&self, id: u64) -> Result<R, Error> {
+        self.store.get(id)
+    }
+
+    pub fn list_records<R: Record>(&self, pagination: &Pagination) -> Result<Page<R>, Error> {
+        self.store.list(pagination)
+    }
+
+    pub fn update_record<R: Record>(&self, id: u64, name: &str) -> Result<R, Error> {
+        self.store.update(id, name)
+    }
+
+    pub fn delete_record<R: Record>(&self, id: u64) -> Result<R, Error> {
+        self.store.delete(id)
+    }
+
+    pub fn publish_user(&self, user: User) -> Result<(), Error> {
+        let msg = user.to_msg()?;
+        Ok(self.user_pub.publish(&msg)?)
+    }
+
+    pub fn publish_task(&self, task: Task) -> Result<(), Error> {
+        let msg = task.to_msg()?;
+        Ok(self.task_pub.publish(&msg)?)
+    }
+
+    pub fn topics(&self) -> Vec<TopicBridgeStatus> {
+        self.bridges.iter().map(|bridge| bridge.status()).collect()
+    }
+
+    fn bridge(&self, name: &str) -> Result<&TopicBridge, Error> {
+        self.bridges
+            .iter()
+            .find(|bridge| bridge.name() == name)
+            .ok_or_else(|| Error::TopicNotFound(name.to_string()))
+    }
+
+    /// Returns the latest message of the bridge named `name`.
+    pub fn topic_message(&self, name: &str) -> Result<TopicMessage, Error> {
+        self.bridge(name)?.latest()
+    }
+
+    pub fn subscribe_topic(&self, name: &str) -> Result<broadcast::Receiver<TopicMessage>, Error> {
+        Ok(self.bridge(name)?.subscribe_messages())
+    }
+
+    /// Publishes a message on a topic of the allow-list.
+    pub fn publish_topic(
+        &self,
+        name: &str,
+        request: PublishMessage,
+    ) -> Result<PublishedMessage, Error> {
+        self.publishers.publish(name, request)
+    }
+
+    /// Calls a ROS service with a JSON request.
+    pub fn call_service(
+        &self,
+        name: &str,
+        request: CallService,
+    ) -> Result<tokio::task::JoinHandle<Result<ServiceResponse, Error>>, Error> {
+        self.services.call(name, request)
+    }
+
+    pub fn actions(&self) -> Vec<ActionStatus> {
+        self.actions.iter().map(|action| action.status()).collect()
+    }
+
+    fn action(&self, name: &str) -> Result<&JsonActionExecutor, Error> {
+        self.actions
+            .iter()
+            .find(|action| action.name() == name)
+            .ok_or_else(|| Error::ActionNotFound(name.to_string()))
+    }
+
+    /// Sends a JSON goal to the action named `name`.
+    pub fn send_action_goal(
+        &self,
+        name: &str,
+        request: SendActionGoal,
+    ) -> Result<GoalAcceptance, Error> {
+        self.action(name)?.send_goal(request)
+    }
+
+    pub fn get_action_goal(&self, name: &str, goal_id: &Uuid) -> Result<Execution, Error> {
+        self.action(name)?.goal(goal_id)
+    }
+
+    pub fn subscribe_action_goal(
+        &self,
+        name: &str,
+        goal_id: &Uuid,
+    ) -> Result<Option<broadcast::Receiver<ExecutionEvent>>, Error> {
+        self.action(name)?.subscribe_goal(goal_id)
+    }
+
+    pub fn cancel_action_goal(
+        &self,
+        name: &str,
+        goal_id: &Uuid,
+    ) -> Result<tokio::task::JoinHandle<Result<(), Error>>, Error> {
+        self.action(name)?.cancel_goal_by_id(goal_id)
+    }
+
+    /// Returns the controller named `name`, or the default controller.
+    fn controller(
+        &self,
+        name: Option<&str>,
+    ) -> Result<&FollowJointTrajectoryActionExecutor, Error> {
+        match name {
+            Some(name) => self
+                .controllers
+                .iter()
+                .find(|controller| controller.name() == name)
+                .ok_or_else(|| Error::ControllerNotFound(name.to_string())),
+            None => Ok(&self.controllers[0]),
+        }
+    }
+
+    pub fn controllers(&self) -> Vec<ControllerStatus> {
+        self.controllers
+            .iter()
+            .map(|controller| controller.status())
+            .collect()
+    }
+
+    pub fn controller_status(&self, name: &str) -> Result<ControllerStatus, Error> {
+        Ok(self.controller(Some(name))?.status())
+    }
+
+    pub fn execute_follow_joint_trajectory(
+        &self,
+        controller: Option<&str>,
+        trajectory: JointTrajectory,
+        complete_condition: Option<CompleteCondition>,
+        timeouts: &TimeoutOverrides,
+    ) -> Result<Submission, Error> {
+        let controller = self.controller(controller)?;
+        trajectory
+            .validate(&controller.joint_limits())
+            .map_err(Error::InvalidTrajectory)?;
+        let trajectory = trajectory.to_msg().map_err(Error::InvalidTrajectory)?;
+        controller.submit(
+            trajectory,
+            complete_condition.map(|c| c.to_arci()),
+            timeouts,
+        )
+    }
+
+    /// Sends one goal per controller, all stamped with the same start time.
+    ///
+    /// Every controller must be idle. If a goal is not accepted, the goals
+    /// already accepted are canceled.
+    pub fn execute_synchronized(
+        &self,
+        request: ExecuteControllers,
+    ) -> Result<tokio::task::JoinHandle<Result<SynchronizedExecution, Error>>, Error> {
+        let (start_time, acceptances) =
+            self.send_synchronized(request.goals, request.start_delay)?;
+
+        Ok(tokio::spawn(async move {
+            let (accepted, mut rejected) = accept_all(acceptances).await;
+            if let Some((_, e)) = rejected.pop() {
+                cancel_all(&accepted);
+                return Err(e);
+            }
+
+            Ok(SynchronizedExecution {
+                start_time,
+                goals: accepted
+                    .into_iter()
+                    .map(|(controller, goal_id)| (controller.name().to_string(), goal_id))
+                    .collect(),
+            })
+        }))
+    }
+
+    /// Splits a trajectory over the joints of several controllers into one
+    /// goal per controller and sends them with the same start time.
+    ///
+    /// In atomic mode, every piece is canceled if one of them is rejected or
+    /// does not succeed. Otherwise the accepted pieces keep running.
+    pub fn execute_merged(
+        &self,
+        request: ExecuteMergedTrajectory,
+    ) -> Result<tokio::task::JoinHandle<Result<CombinedExecution, Error>>, Error> {
+        let goals = self
+            .split_trajectory(&request.trajectory)?
+            .into_iter()
+            .map(|(name, trajectory)| {
+                let goal = ExecuteTrajectory {
+                    trajectory,
+                    complete_condition: None,
+                    timeouts: request.timeouts,
+                };
+                (name, goal)
+            })
+            .collect();
+        let (start_time, acceptances) = self.send_synchronized(goals, request.start_delay)?;
+
+        let executions = self.executions.clone();
+        let atomic = request.atomic;
+        Ok(tokio::spawn(async move {
+            let (accepted, mut rejected) = accept_all(acceptances).await;
+            if accepted.is_empty() || (atomic && !rejected.is_empty()) {
+                cancel_all(&accepted);
+                if let Some((_, e)) = rejected.pop() {
+                    return Err(e);
+                }
+            }
+
+            let combined = CombinedExecution {
+                id: Uuid::new_v4(),
+                atomic,
+                start_time,
+                goals: accepted
+                    .iter()
+                    .map(|(controller, goal_id)| (controller.name().to_string(), *goal_id))
+                    .collect(),
+                rejected: rejected
+                    .iter()
+                    .map(|(controller, e)| (controller.name().to_string(), e.to_string()))
+                    .collect(),
+                status: ExecutionStatus::Executing,
+                finished_at: None,
+            };
+            executions.combined_started(combined.clone());
+            let initial_status = if rejected.is_empty() {
+                ExecutionStatus::Succeeded
+            } else {
+                ExecutionStatus::Aborted
+            };
+            tokio::spawn(monitor_combined(
+                executions,
+                combined.id,
+                accepted,
+                atomic,
+                initial_status,
+            ));
+            Ok(combined)
+        }))
+    }
+
+    pub fn get_combined_execution(&self, id: &Uuid) -> Result<CombinedExecution, Error> {
+        self.executions
+            .get_combined(id)
+            .ok_or(Error::GoalNotFound(*id))
+    }
+
+    /// Splits `trajectory` by the joints of each controller, in configuration
+    /// order. Every joint must belong to exactly one controller.
+    fn split_trajectory(
+        &self,
+        trajectory: &JointTrajectory,
+    ) -> Result<Vec<(String, JointTrajectory)>, Error> {
+        let controller_joints: Vec<(String, Vec<String>)> = self
+            .controllers
+            .iter()
+            .map(|controller| {
+                (
+                    controller.name().to_string(),
+                    controller.joint_limits().joint_names(),
+                )
+            })
+            .collect();
+        split_by_joints(trajectory, &controller_joints)
+    }
+
+    /// Validates and sends one goal per controller, all stamped with ROS now
+    /// + `start_delay`. Returns the start time in seconds.
+    ///
+    /// Every goal is checked before the first one is sent. If a goal still
+    /// cannot be sent, the goals already sent are canceled once accepted.
+    fn send_synchronized(
+        &self,
+        goals: BTreeMap<String, ExecuteTrajectory>,
+        start_delay: f64,
+    ) -> Result<
+        (
+            f64,
+            Vec<(FollowJointTrajectoryActionExecutor, GoalAcceptance)>,
+        ),
+        Error,
+    > {
+        let start_delay =
+            Duration::try_from_secs_f64(start_delay).map_err(|_| Error::InvalidTimeout {
+                name: "start_delay",
+                value: start_delay,
+            })?;
+
+        let mut clock = r2r::Clock::create(r2r::ClockType::RosTime)?;
+        let start_time = clock.get_now()? + start_delay;
+        let stamp = r2r::Clock::to_builtin_time(&start_time);
+
+        let mut prepared = vec![];
+        for (name, goal) in goals {
+            let controller = self.controller(Some(&name))?;
+            if controller.is_busy() {
+                return Err(Error::Busy);
+            }
+            goal.trajectory
+                .validate(&controller.joint_limits())
+                .map_err(Error::InvalidTrajectory)?;
+            let mut trajectory = goal.trajectory.to_msg().map_err(Error::InvalidTrajectory)?;
+            trajectory.header.stamp = stamp.clone();
+            let goal = controller.prepare(
+                trajectory,
+                goal.complete_condition.map(|c| c.to_arci()),
+                &goal.timeouts,
+            )?;
+            prepared.push((controller.clone(), goal));
+        }
+
+        let mut acceptances = vec![];
+        for (controller, goal) in prepared {
+            let res = match controller.submit_prepared(goal) {
+                Ok(Submission::Sent(acceptance)) => {
+                    acceptances.push((controller, acceptance));
+                    continue;
+                }
+                // the controllers were idle, so nothing is queued
+                Ok(Submission::Queued(queued)) => {
+                    let _ = controller.remove_queued_goal(&queued.queue_id);
+                    Error::Busy
+                }
+                Err(e) => e,
+            };
+            log::error!("failed to send synchronized goals: {:?}", res);
+            tokio::spawn(async move {
+                let (accepted, _) = accept_all(acceptances).await;
+                cancel_all(&accepted);
+            });
+            return Err(res);
+        }
+        Ok((start_time.as_secs_f64(), acceptances))
+    }
+
+    /// Moves the joints of `request` to their targets with an interpolated
+    /// trajectory starting from the current joint states. The other joints of
+    /// the controller keep their current positions.
+    pub fn move_joints(&self, request: MoveJoints) -> Result<(JointTrajectory, Submission), Error> {
+        let joint_limits = self
+            .controller(request.controller.as_deref())?
+            .joint_limits();
+        let joint_names = if joint_limits.joints.is_empty() {
+            request.positions.keys().cloned().collect()
+        } else {
+            joint_limits.joint_names()
+        };
+        if let Some(name) = request
+            .positions
+            .keys()
+            .find(|name| !joint_names.contains(name))
+        {
+            return Err(Error::InvalidTrajectory(arci::Error::NoJoint(name.clone())));
+        }
+
+        let start = current_positions(&self.joint_states, &joint_names, JOINT_STATE_STALE_AFTER)?;
+        let goal: Vec<f64> = joint_names
+            .iter()
+            .zip(&start)
+            .map(|(name, position)| request.positions.get(name).copied().unwrap_or(*position))
+            .collect();
+        let velocity_limits: Vec<_> = joint_names
+            .iter()
+            .map(|name| joint_limits.get(name).and_then(|limit| limit.velocity))
+            .collect();
+
+        let duration = interpolation::motion_duration(
+            &joint_names,
+            &start,
+            &goal,
+            &velocity_limits,
+            request.duration,
+            request.speed_scale,
+            request.interpolation,
+        )
+        .map_err(Error::InvalidTrajectory)?;
+        let points = interpolation::interpolate(&start, &goal, duration, request.interpolation)
+            .map_err(Error::InvalidTrajectory)?;
+        let trajectory = JointTrajectory {
+            joint_names,
+            points,
+        };
+
+        let submission = self.execute_follow_joint_trajectory(
+            request.controller.as_deref(),
+            trajectory.clone(),
+            request.complete_condition,
+            &request.timeouts,
+        )?;
+        Ok((trajectory, submission))
+    }
+
+    pub fn get_follow_joint_trajectory(&self, goal_id: &Uuid) -> Option<Execution> {
+        self.executions.get(goal_id)
+    }
+
+    /// Waits until the goal has finished and returns its final status.
+    pub async fn wait_follow_joint_trajectory(&self, goal_id: &Uuid) -> ExecutionStatus {
+        self.executions.wait_finished(goal_id).await
+    }
+
+    pub fn subscribe_follow_joint_trajectory(
+        &self,
+        goal_id: &Uuid,
+    ) -> Option<broadcast::Receiver<ExecutionEvent>> {
+        self.executions.subscribe(goal_id)
+    }
+
+    pub fn subscribe_goal_events(&self) -> broadcast::Receiver<GoalEvent> {
+        self.executions.subscribe_goal_events()
     }
 
-    pub fn publish_task(&self, task: Task) -> r2r::Result<()> {
-        let msg = task.to_msg();
-        self.task_pub.publish(&msg)
+    pub fn goal_policy(&self, controller: Option<&str>) -> Result<GoalPolicy, Error> {
+        Ok(self.controller(controller)?.goal_policy())
     }
 
-    pub fn execute_follow_joint_trajectory(&self) -> r2r::Result<tokio::task::JoinHandle<()>> {
-        self.follow_joint_trajectory_action_executor.send_goal()
+    pub fn set_goal_policy(
+        &self,
+        controller: Option<&str>,
+        policy: GoalPolicy,
+    ) -> Result<(), Error> {
+        self.controller(controller)?.set_goal_policy(policy);
+        Ok(())
     }
 
-    pub fn cancel_follow_joint_trajectory(&self) -> Result<tokio::task::JoinHandle<()>, Error> {
-        self.follow_joint_trajectory_action_executor.cancel_goal()
+    pub fn execution_timeouts(&self, controller: Option<&str>) -> Result<ExecutionTimeouts, Error> {
+        Ok(self.controller(controller)?.timeouts())
+    }
+
+    pub fn set_execution_timeouts(
+        &self,
+        controller: Option<&str>,
+        timeouts: ExecutionTimeouts,
+    ) -> Result<(), Error> {
+        self.controller(controller)?.set_timeouts(timeouts)
+    }
+
+    pub fn joint_limits(&self, controller: Option<&str>) -> Result<JointLimits, Error> {
+        Ok(self.controller(controller)?.joint_limits())
+    }
+
+    pub fn set_joint_limits(
+        &self,
+        controller: Option<&str>,
+        joint_limits: JointLimits,
+    ) -> Result<(), Error> {
+        self.controller(controller)?.set_joint_limits(joint_limits);
+        Ok(())
+    }
+
+    /// Returns the latest state of every joint, sorted by name.
+    pub fn joint_states(&self) -> Result<Vec<JointState>, Error> {
+        if self.joint_states.is_empty() {
+            return Err(Error::NoJointStateAvailable);
+        }
+        let mut joint_states: Vec<_> = self
+            .joint_states
+            .all()
+            .into_iter()
+            .map(|(name, entry)| JointState::from_entry(name, &entry, JOINT_STATE_STALE_AFTER))
+            .collect();
+        joint_states.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(joint_states)
+    }
+
+    pub fn joint_state(&self, name: &str) -> Result<JointState, Error> {
+        joint_state(&self.joint_states, name, JOINT_STATE_STALE_AFTER)
+    }
+
+    pub fn robot_model(&self) -> Option<RobotModel> {
+        self.robot_model.lock().unwrap().clone()
+    }
+
+    /// Loads the URDF and uses the limits of `joint_names`, or of every movable
+    /// joint, to validate trajectories. Files other than the one of the config
+    /// are not read.
+    pub fn load_robot_model(
+        &self,
+        source: UrdfSource,
+        joint_names: Option<Vec<String>>,
+    ) -> Result<tokio::task::JoinHandle<Result<RobotModel, Error>>, Error> {
+        if let UrdfSource::File { path } = &source {
+            if self.urdf_file.as_ref() != Some(path) {
+                return Err(Error::UrdfFileNotAllowed(path.clone()));
+            }
+        }
+        let node = self.node.clone();
+        let controllers = self.controllers.clone();
+        let robot_model = self.robot_model.clone();
+        Ok(tokio::spawn(async move {
+            let model = urdf::load_robot_model(&node, &source).await?;
+            // controllers keep their joints, the others get `joint_names`
+            let mut controller_limits = vec![];
+            for controller in &controllers {
+                let configured = controller.joint_limits().joint_names();
+                let joint_names = if configured.is_empty() {
+                    joint_names.clone()
+                } else {
+                    Some(configured)
+                };
+                controller_limits.push(model.joint_limits(joint_names.as_deref())?);
+            }
+            log::info!(
+                "robot model {} loaded from {:?}: {} joints",
+                model.name,
+                source,
+                model.joints.len()
+            );
+            for (controller, joint_limits) in controllers.iter().zip(controller_limits) {
+                controller.set_joint_limits(joint_limits);
+            }
+            robot_model.lock().unwrap().replace(model.clone());
+            Ok(model)
+        }))
+    }
+
+    pub fn queued_goals(&self, controller: Option<&str>) -> Result<Vec<QueuedGoal>, Error> {
+        Ok(self.controller(controller)?.queued_goals())
+    }
+
+    pub fn reorder_queue(
+        &self,
+        controller: Option<&str>,
+        order: &[Uuid],
+    ) -> Result<Vec<QueuedGoal>, Error> {
+        self.controller(controller)?.reorder_queue(order)
+    }
+
+    pub fn remove_queued_goal(
+        &self,
+        controller: Option<&str>,
+        queue_id: &Uuid,
+    ) -> Result<QueuedGoal, Error> {
+        self.controller(controller)?.remove_queued_goal(queue_id)
+    }
+
+    pub fn clear_queue(&self, controller: Option<&str>) -> Result<Vec<QueuedGoal>, Error> {
+        Ok(self.controller(controller)?.clear_queue())
+    }
+
+    pub fn cancel_follow_joint_trajectory(
+        &self,
+        controller: Option<&str>,
+    ) -> Result<tokio::task::JoinHandle<Result<(), Error>>, Error> {
+        self.controller(controller)?.cancel_goal()
+    }
+
+    /// Cancels the goals of every controller and action, waiting at most
+    /// `wait` for the cancel responses, then stops spinning the node.
+    pub async fn shutdown(&self, wait: Duration) {
+        let handlers: Vec<_> = self
+            .controllers
+            .iter()
+            .flat_map(|controller| controller.cancel_all())
+            .chain(self.actions.iter().flat_map(|action| action.cancel_all()))
+            .collect();
+        log::warn!("shutdown: canceling {} goals", handlers.len());
+        match timeout(wait, futures::future::join_all(handlers)).await {
+            Ok(results) => {
+                for res in results {
+                    match res {
+                        Ok(Ok(())) => {}
+                        Ok(Err(e)) => log::error!("shutdown: failed to cancel goal: {:?}", e),
+                        Err(e) => log::error!("shutdown: cancel task failed: {:?}", e),
+                    }
+                }
+            }
+            Err(_) => log::error!("shutdown: no cancel response within {:?}", wait),
+        }
+        self.node.stop_spin_thread();
+    }
+
+    /// Cancels the goal `goal_id` of the controller's action server, e.g. a
+    /// goal sent by `send-trajectory` in another process.
+    pub fn cancel_follow_joint_trajectory_on_server(
+        &self,
+        controller: Option<&str>,
+        goal_id: Uuid,
+    ) -> Result<tokio::task::JoinHandle<Result<(), Error>>, Error> {
+        self.controller(controller)?.cancel_goal_on_server(goal_id)
+    }
+
+    pub fn cancel_follow_joint_trajectory_goal(
+        &self,
+        goal_id: &Uuid,
+    ) -> Result<tokio::task::JoinHandle<Result<(), Error>>, Error> {
+        // the registry is shared, so only the controller running the goal
+        // knows whether it is active
+        for controller in &self.controllers {
+            match controller.cancel_goal_by_id(goal_id) {
+                Err(Error::GoalNotFound(_) | Error::GoalAlreadyFinished(_)) => continue,
+                res => return res,
+            }
+        }
+        match self.executions.get(goal_id) {
+            Some(_) => Err(Error::GoalAlreadyFinished(*goal_id)),
+            None => Err(Error::GoalNotFound(*goal_id)),
+        }
+    }
+}
+
+type AcceptedGoal = (FollowJointTrajectoryActionExecutor, Uuid);
+
+/// Waits for every goal to be accepted or rejected.
+async fn accept_all(
+    acceptances: Vec<(FollowJointTrajectoryActionExecutor, GoalAcceptance)>,
+) -> (
+    Vec<AcceptedGoal>,
+    Vec<(FollowJointTrajectoryActionExecutor, Error)>,
+) {
+    let mut accepted = vec![];
+    let mut rejected = vec![];
+    for (controller, acceptance) in acceptances {
+        let res = acceptance
+            .await
+            .unwrap_or_else(|_| Err(Error::Other(anyhow::anyhow!("goal request was dropped"))));
+        match res {
+            Ok(goal_id) => accepted.push((controller, goal_id)),
+            Err(e) => {
+                log::error!("synchronized goal of {} failed: {:?}", controller.name(), e);
+                rejected.push((controller, e));
+            }
+        }
+    }
+    (accepted, rejected)
+}
+
+fn cancel_all(goals: &[AcceptedGoal]) {
+    for (controller, goal_id) in goals {
+        if let Err(e) = controller.cancel_goal_by_id(goal_id) {
+            log::error!("failed to cancel goal {}: {:?}", goal_id, e);
+        }
+    }
+}
+
+fn joint_state(
+    joint_states: &JointStateCache,
+    name: &str,
+    stale_after: Duration,
+) -> Result<JointState, Error> {
+    if joint_states.is_empty() {
+        return Err(Error::NoJointStateAvailable);
+    }
+    let entry = joint_states
+        .get(name)
+        .ok_or_else(|| Error::JointNotFound(name.to_string()))?;
+    Ok(JointState::from_entry(
+        name.to_string(),
+        &entry,
+        stale_after,
+    ))
+}
+
+/// Returns the positions of `joint_names`, the start of the trajectories
+/// planned by the gateway. Positions older than `stale_after` are refused.
+fn current_positions(
+    joint_states: &JointStateCache,
+    joint_names: &[String],
+    stale_after: Duration,
+) -> Result<Vec<f64>, Error> {
+    joint_names
+        .iter()
+        .map(|name| {
+            let joint_state = joint_state(joint_states, name, stale_after)?;
+            if joint_state.stale {
+                return Err(Error::StaleJointState(name.clone()));
+            }
+            joint_state
+                .position
+                .ok_or_else(|| Error::JointNotFound(name.clone()))
+        })
+        .collect()
+}
+
+/// Splits `trajectory` into one piece per entry of `controller_joints`, the
+/// name and the joints of each controller, skipping the controllers with no
+/// joint in the trajectory.
+fn split_by_joints(
+    trajectory: &JointTrajectory,
+    controller_joints: &[(String, Vec<String>)],
+) -> Result<Vec<(String, JointTrajectory)>, Error> {
+    // vector lengths are checked before the points are sliced
+    trajectory
+        .validate(&JointLimits::default())
+        .map_err(Error::InvalidTrajectory)?;
+
+    let owned_once = |name: &String| {
+        controller_joints
+            .iter()
+            .filter(|(_, joint_names)| joint_names.contains(name))
+            .count()
+            == 1
+    };
+    if trajectory.joint_names.is_empty() || !trajectory.joint_names.iter().all(owned_once) {
+        return Err(Error::InvalidTrajectory(arci::Error::JointNamesMismatch {
+            partial: trajectory.joint_names.clone(),
+            full: controller_joints
+                .iter()
+                .flat_map(|(_, joint_names)| joint_names.clone())
+                .collect(),
+        }));
+    }
+
+    let mut pieces = vec![];
+    for (controller, joint_names) in controller_joints {
+        let indices: Vec<usize> = trajectory
+            .joint_names
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| joint_names.contains(name))
+            .map(|(i, _)| i)
+            .collect();
+        if !indices.is_empty() {
+            pieces.push((controller.clone(), trajectory.select_joints(&indices)));
+        }
+    }
+    Ok(pieces)
+}
+
+/// Records the combined status once every piece has finished. `status` is
+/// the status so far, i.e. `Succeeded` unless a piece was rejected.
+async fn monitor_combined(
+    executions: ExecutionRegistry,
+    id: Uuid,
+    goals: Vec<AcceptedGoal>,
+    atomic: bool,
+    mut status: ExecutionStatus,
+) {
+    let mut pending: FuturesUnordered<_> = goals
+        .iter()
+        .map(|(_, goal_id)| {
+            let executions = executions.clone();
+            let goal_id = *goal_id;
+            async move { (goal_id, executions.wait_finished(&goal_id).await) }
+        })
+        .collect();
+
+    while let Some((goal_id, piece_status)) = pending.next().await {
+        if piece_status == ExecutionStatus::Succeeded || status != ExecutionStatus::Succeeded {
+            continue;
+        }
+        // the first failure decides the combined status
+        status = piece_status;
+        if atomic {
+            log::info!("piece {} of {} ended as {:?}", goal_id, id, piece_status);
+            let others: Vec<AcceptedGoal> = goals
+                .iter()
+                .filter(|(_, other)| *other != goal_id)
+                .cloned()
+                .collect();
+            cancel_all(&others);
+        }
+    }
+    executions.combined_finished(&id, status);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::trajectory::JointTrajectoryPoint;
+    use r2r::sensor_msgs;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn controller_joints() -> Vec<(String, Vec<String>)> {
+        vec![
+            ("arm".to_string(), names(&["shoulder", "elbow"])),
+            ("head".to_string(), names(&["pan", "tilt"])),
+            ("gripper".to_string(), names(&["finger"])),
+        ]
+    }
+
+    fn trajectory(joint_names: &[&str]) -> JointTrajectory {
+        let values = |offset: f64| -> Vec<f64> {
+            (0..joint_names.len()).map(|i| offset + i as f64).collect()
+        };
+        JointTrajectory {
+            joint_names: names(joint_names),
+            points: vec![JointTrajectoryPoint {
+                positions: values(0.0),
+                velocities: values(10.0),
+                accelerations: vec![],
+                effort: vec![],
+                time_from_start: 1.0,
+            }],
+        }
+    }
+
+    #[test]
+    fn split_by_controller() {
+        let trajectory = trajectory(&["pan", "elbow", "shoulder"]);
+        let pieces = split_by_joints(&trajectory, &controller_joints()).unwrap();
+        assert_eq!(pieces.len(), 2);
+
+        let (arm, arm_trajectory) = &pieces[0];
+        assert_eq!(arm, "arm");
+        // joints keep the order of the request
+        assert_eq!(arm_trajectory.joint_names, names(&["elbow", "shoulder"]));
+        assert_eq!(arm_trajectory.points[0].positions, vec![1.0, 2.0]);
+        assert_eq!(arm_trajectory.points[0].velocities, vec![11.0, 12.0]);
+        assert!(arm_trajectory.points[0].accelerations.is_empty());
+        assert_eq!(arm_trajectory.points[0].time_from_start, 1.0);
+
+        let (head, head_trajectory) = &pieces[1];
+        assert_eq!(head, "head");
+        assert_eq!(head_trajectory.joint_names, names(&["pan"]));
+        assert_eq!(head_trajectory.points[0].positions, vec![0.0]);
+    }
+
+    #[test]
+    fn split_rejects_unknown_or_shared_joints() {
+        for joint_names in [vec![], vec!["pan", "wheel"]] {
+            assert!(matches!(
+                split_by_joints(&trajectory(&joint_names), &controller_joints()),
+                Err(Error::InvalidTrajectory(
+                    arci::Error::JointNamesMismatch { .. }
+                ))
+            ));
+        }
+
+        let mut shared = controller_joints();
+        shared[2].1.push("pan".to_string());
+        assert!(matches!(
+            split_by_joints(&trajectory(&["pan"]), &shared),
+            Err(Error::InvalidTrajectory(
+                arci::Error::JointNamesMismatch { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn split_checks_lengths_first() {
+        let mut trajectory = trajectory(&["pan", "tilt"]);
+        trajectory.points[0].positions.pop();
+        assert!(matches!(
+            split_by_joints(&trajectory, &controller_joints()),
+            Err(Error::InvalidTrajectory(arci::Error::LengthMismatch { .. }))
+        ));
+    }
+
+    fn joint_state_msg(names: &[&str], positions: &[f64]) -> sensor_msgs::msg::JointState {
+        sensor_msgs::msg::JointState {
+            name: self::names(names),
+            position: positions.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn current_positions_of_cached_joints() {
+        let joint_states = JointStateCache::default();
+        let joint_names = names(&["shoulder", "elbow"]);
+        assert!(matches!(
+            current_positions(&joint_states, &joint_names, Duration::from_secs(1)),
+            Err(Error::NoJointStateAvailable)
+        ));
+
+        joint_states.update(&joint_state_msg(&["shoulder"], &[0.5]));
+        assert!(matches!(
+            current_positions(&joint_states, &joint_names, Duration::from_secs(1)),
+            Err(Error::JointNotFound(name)) if name == "elbow"
+        ));
+
+        // published without positions
+        joint_states.update(&joint_state_msg(&["elbow"], &[]));
+        assert!(matches!(
+            current_positions(&joint_states, &joint_names, Duration::from_secs(1)),
+            Err(Error::JointNotFound(name)) if name == "elbow"
+        ));
+
+        joint_states.update(&joint_state_msg(&["elbow"], &[1.0]));
+        assert_eq!(
+            current_positions(&joint_states, &joint_names, Duration::from_secs(1)).unwrap(),
+            vec![0.5, 1.0]
+        );
+    }
+
+    #[test]
+    fn stale_positions_are_refused() {
+        let joint_states = JointStateCache::default();
+        joint_states.update(&joint_state_msg(&["shoulder"], &[0.5]));
+        std::thread::sleep(Duration::from_millis(20));
+        joint_states.update(&joint_state_msg(&["elbow"], &[1.0]));
+
+        let stale_after = Duration::from_millis(10);
+        assert!(matches!(
+            current_positions(&joint_states, &names(&["elbow", "shoulder"]), stale_after),
+            Err(Error::StaleJointState(name)) if name == "shoulder"
+        ));
+        assert_eq!(
+            current_positions(&joint_states, &names(&["elbow"]), stale_after).unwrap(),
+            vec![1.0]
+        );
     }
 }
@@ -0,0 +1,297 @@
+use crate::models::motion::Interpolation;
+use crate::models::trajectory::{JointTrajectoryPoint, MAX_TIME_FROM_START};
+
+// spacing of the points of a minimum-jerk trajectory, in seconds
+const POINT_INTERVAL: f64 = 0.1;
+const MAX_POINTS: usize = 1000;
+// shortest motion, so that even a motion to the current pose has a valid timing
+const MIN_DURATION: f64 = 0.1;
+
+/// Peak velocity of the interpolation divided by the average velocity.
+fn peak_velocity_ratio(interpolation: Interpolation) -> f64 {
+    match interpolation {
+        Interpolation::Linear => 1.0,
+        Interpolation::MinimumJerk => 1.875,
+    }
+}
+
+/// Fails unless `duration` is positive and at most `MAX_TIME_FROM_START`.
+fn check_duration(duration: f64) -> Result<f64, arci::Error> {
+    if !(duration > 0.0 && duration <= MAX_TIME_FROM_START) {
+        return Err(arci::Error::InterpolationError(format!(
+            "duration must be positive and at most {} seconds: {}",
+            MAX_TIME_FROM_START, duration
+        )));
+    }
+    Ok(duration)
+}
+
+/// Returns the duration of the motion from `start` to `goal`.
+///
+/// With `duration`, checks that no joint exceeds its velocity limit. Otherwise
+/// returns the shortest duration within the limits scaled by `speed_scale`.
+/// `velocity_limits` has the limit of each joint, `None` if unknown. The
+/// duration never exceeds `MAX_TIME_FROM_START`.
+pub fn motion_duration(
+    joint_names: &[String],
+    start: &[f64],
+    goal: &[f64],
+    velocity_limits: &[Option<f64>],
+    duration: Option<f64>,
+    speed_scale: Option<f64>,
+    interpolation: Interpolation,
+) -> Result<f64, arci::Error> {
+    let ratio = peak_velocity_ratio(interpolation);
+
+    if let Some(duration) = duration {
+        check_duration(duration)?;
+        for (i, name) in joint_names.iter().enumerate() {
+            let Some(limit) = velocity_limits[i] else {
+                continue;
+            };
+            let velocity = ratio * (goal[i] - start[i]).abs() / duration;
+            if velocity > limit {
+                return Err(arci::Error::VelocityOutOfLimit {
+                    name: name.clone(),
+                    velocity,
+                    limit,
+                });
+            }
+        }
+        return Ok(duration);
+    }
+
+    let speed_scale = speed_scale.unwrap_or(1.0);
+    if !(speed_scale.is_finite() && speed_scale > 0.0) {
+        return Err(arci::Error::InterpolationError(format!(
+            "speed_scale must be positive: {}",
+            speed_scale
+        )));
+    }
+    let mut duration = MIN_DURATION;
+    for (i, name) in joint_names.iter().enumerate() {
+        let distance = (goal[i] - start[i]).abs();
+        if distance == 0.0 {
+            continue;
+        }
+        let limit = velocity_limits[i]
+            .filter(|limit| *limit > 0.0)
+            .ok_or_else(|| {
+                arci::Error::InterpolationError(format!(
+                    "velocity limit of {} is unknown, give a duration",
+                    name
+                ))
+            })?;
+        duration = duration.max(ratio * distance / (limit * speed_scale));
+    }
+    // e.g. a tiny speed_scale
+    check_duration(duration)
+}
+
+/// Creates the points of a motion from `start` to `goal` taking `duration`
+/// seconds. The start point itself is not included.
+pub fn interpolate(
+    start: &[f64],
+    goal: &[f64],
+    duration: f64,
+    interpolation: Interpolation,
+) -> Result<Vec<JointTrajectoryPoint>, arci::Error> {
+    check_duration(duration)?;
+    if start.len() != goal.len() {
+        return Err(arci::Error::LengthMismatch {
+            model: start.len(),
+            input: goal.len(),
+        });
+    }
+
+    match interpolation {
+        // the controller interpolates linearly between points without velocities
+        Interpolation::Linear => Ok(vec![JointTrajectoryPoint {
+            positions: goal.to_vec(),
+            velocities: vec![],
+            accelerations: vec![],
+            effort: vec![],
+            time_from_start: duration,
+        }]),
+        Interpolation::MinimumJerk => {
+            let num_points = ((duration / POINT_INTERVAL).ceil() as usize).clamp(2, MAX_POINTS);
+            let points = (1..=num_points)
+                .map(|i| {
+                    let tau = i as f64 / num_points as f64;
+                    // s(tau) = 10 tau^3 - 15 tau^4 + 6 tau^5 and its derivatives
+                    let s = tau.powi(3) * (10.0 - 15.0 * tau + 6.0 * tau.powi(2));
+                    let ds = 30.0 * tau.powi(2) * (1.0 - tau).powi(2);
+                    let dds = 60.0 * tau * (1.0 - tau) * (1.0 - 2.0 * tau);
+                    let deltas = start.iter().zip(goal).map(|(s0, g)| g - s0);
+                    JointTrajectoryPoint {
+                        positions: start
+                            .iter()
+                            .zip(goal)
+                            .map(|(s0, g)| s0 + (g - s0) * s)
+                            .collect(),
+                        velocities: deltas.clone().map(|d| d * ds / duration).collect(),
+                        accelerations: deltas.map(|d| d * dds / duration.powi(2)).collect(),
+                        effort: vec![],
+                        time_from_start: duration * tau,
+                    }
+                })
+                .collect();
+            Ok(points)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> Vec<String> {
+        vec!["a".to_string(), "b".to_string()]
+    }
+
+    #[test]
+    fn motion_duration_from_velocity_limits() {
+        let limits = [Some(1.0), Some(0.5)];
+        let duration = |start: &[f64], goal: &[f64], speed_scale, interpolation| {
+            motion_duration(
+                &names(),
+                start,
+                goal,
+                &limits,
+                None,
+                speed_scale,
+                interpolation,
+            )
+        };
+        // the slowest joint decides
+        let linear = duration(&[0.0, 0.0], &[1.0, 1.0], None, Interpolation::Linear).unwrap();
+        assert!((linear - 2.0).abs() < 1e-9);
+        let minimum_jerk =
+            duration(&[0.0, 0.0], &[1.0, 1.0], None, Interpolation::MinimumJerk).unwrap();
+        assert!((minimum_jerk - 3.75).abs() < 1e-9);
+        let scaled = duration(&[0.0, 0.0], &[1.0, 1.0], Some(0.5), Interpolation::Linear).unwrap();
+        assert!((scaled - 4.0).abs() < 1e-9);
+        // a motion to the current pose still takes some time
+        let still = duration(&[0.5, 0.5], &[0.5, 0.5], None, Interpolation::Linear).unwrap();
+        assert_eq!(still, MIN_DURATION);
+    }
+
+    #[test]
+    fn motion_duration_rejects_invalid_speed_scale() {
+        let limits = [Some(1.0), Some(1.0)];
+        for speed_scale in [0.0, -1.0, f64::NAN, f64::INFINITY, 1e-300] {
+            let res = motion_duration(
+                &names(),
+                &[0.0, 0.0],
+                &[1.0, 1.0],
+                &limits,
+                None,
+                Some(speed_scale),
+                Interpolation::Linear,
+            );
+            assert!(
+                matches!(res, Err(arci::Error::InterpolationError(_))),
+                "{}",
+                speed_scale
+            );
+        }
+    }
+
+    #[test]
+    fn motion_duration_needs_velocity_limits() {
+        let res = motion_duration(
+            &names(),
+            &[0.0, 0.0],
+            &[1.0, 0.0],
+            &[None, Some(1.0)],
+            None,
+            None,
+            Interpolation::Linear,
+        );
+        assert!(matches!(res, Err(arci::Error::InterpolationError(_))));
+        // a joint which does not move needs no limit
+        motion_duration(
+            &names(),
+            &[0.0, 0.0],
+            &[0.0, 1.0],
+            &[None, Some(1.0)],
+            None,
+            None,
+            Interpolation::Linear,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn motion_duration_checks_given_duration() {
+        let limits = [Some(1.0), None];
+        let duration = |duration| {
+            motion_duration(
+                &names(),
+                &[0.0, 0.0],
+                &[1.0, 10.0],
+                &limits,
+                Some(duration),
+                None,
+                Interpolation::Linear,
+            )
+        };
+        assert_eq!(duration(1.0).unwrap(), 1.0);
+        assert!(matches!(
+            duration(0.5),
+            Err(arci::Error::VelocityOutOfLimit { .. })
+        ));
+        for invalid in [0.0, -1.0, f64::NAN, f64::INFINITY, 1e30] {
+            assert!(matches!(
+                duration(invalid),
+                Err(arci::Error::InterpolationError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn interpolate_linear() {
+        let points = interpolate(&[0.0, 1.0], &[1.0, 0.0], 2.0, Interpolation::Linear).unwrap();
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].positions, vec![1.0, 0.0]);
+        assert_eq!(points[0].time_from_start, 2.0);
+    }
+
+    #[test]
+    fn interpolate_minimum_jerk() {
+        let points =
+            interpolate(&[0.0, 1.0], &[1.0, 0.0], 1.0, Interpolation::MinimumJerk).unwrap();
+        assert_eq!(points.len(), 10);
+        let last = points.last().unwrap();
+        assert_eq!(last.time_from_start, 1.0);
+        for (position, goal) in last.positions.iter().zip([1.0, 0.0]) {
+            assert!((position - goal).abs() < 1e-9);
+        }
+        // the motion stops at the goal
+        assert!(last.velocities.iter().all(|v| v.abs() < 1e-9));
+        assert!(last.accelerations.iter().all(|a| a.abs() < 1e-9));
+        // peak velocity at the middle
+        let middle = &points[4];
+        assert!((middle.velocities[0] - 1.875).abs() < 1e-9);
+        assert!(points
+            .windows(2)
+            .all(|w| w[1].time_from_start > w[0].time_from_start));
+
+        let long = interpolate(&[0.0], &[1.0], 1000.0, Interpolation::MinimumJerk).unwrap();
+        assert_eq!(long.len(), MAX_POINTS);
+    }
+
+    #[test]
+    fn interpolate_rejects_invalid_input() {
+        assert!(matches!(
+            interpolate(&[0.0, 0.0], &[1.0], 1.0, Interpolation::Linear),
+            Err(arci::Error::LengthMismatch { .. })
+        ));
+        for duration in [0.0, f64::NAN, f64::INFINITY, 1e30] {
+            assert!(matches!(
+                interpolate(&[0.0], &[1.0], duration, Interpolation::MinimumJerk),
+                Err(arci::Error::InterpolationError(_))
+            ));
+        }
+    }
+}
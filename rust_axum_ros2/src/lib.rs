@@ -1,5 +1,14 @@
+pub mod action;
+pub mod bridge;
+pub mod cli;
+pub mod config;
 pub mod error;
 pub mod gateway;
+pub mod interpolation;
 pub mod logger;
 pub mod models;
+pub mod registry;
+pub mod service;
+pub mod store;
 pub mod trajectory;
+pub mod urdf;
@@ -1,6 +1,7 @@
+use crate::config::LoggerConfig;
 use fern;
 
-pub fn setup_logger() -> Result<(), fern::InitError> {
+pub fn setup_logger(config: &LoggerConfig) -> Result<(), fern::InitError> {
     use fern::colors::{Color, ColoredLevelConfig};
 
     // configure colors for the whole line
@@ -34,26 +35,28 @@ pub fn setup_logger() -> Result<(), fern::InitError> {
                 message = message,
             ))
         })
-        .level(log::LevelFilter::Trace)
+        .level(config.stdout_level)
         .chain(std::io::stdout());
 
-    let file_config = fern::Dispatch::new()
-        .format(move |out, message, record| {
-            out.finish(format_args!(
-                "[{date}][{target}][{level}] {message}",
-                date = chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
-                target = record.target(),
-                level = record.level(),
-                message = message,
-            ))
-        })
-        .level(log::LevelFilter::Info)
-        .chain(fern::log_file("log/output.log")?);
-
-    base_config
-        .chain(stdout_config)
-        .chain(file_config)
-        .apply()?;
+    let mut base_config = base_config.chain(stdout_config);
+
+    if let Some(file) = &config.file {
+        let file_config = fern::Dispatch::new()
+            .format(move |out, message, record| {
+                out.finish(format_args!(
+                    "[{date}][{target}][{level}] {message}",
+                    date = chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+                    target = record.target(),
+                    level = record.level(),
+                    message = message,
+                ))
+            })
+            .level(config.file_level)
+            .chain(fern::log_file(file)?);
+        base_config = base_config.chain(file_config);
+    }
+
+    base_config.apply()?;
 
     Ok(())
 }
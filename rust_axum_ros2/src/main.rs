@@ -1,16 +1,49 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
-    routing::{get, post},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    http::{header, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+    routing::{delete, get, post},
     Json, Router,
 };
+use futures::stream::{self, Stream};
+use rust_axum_ros2::cli::{self, Cli, Command};
+use rust_axum_ros2::config::Config;
+use rust_axum_ros2::error::Error;
 use rust_axum_ros2::gateway::Gateway;
 use rust_axum_ros2::logger::setup_logger;
-use rust_axum_ros2::models::task::{CreateTask, Task};
+use rust_axum_ros2::models::action::{ActionStatus, SendActionGoal};
+use rust_axum_ros2::models::controller::{
+    CombinedExecution, ControllerQuery, ControllerStatus, ExecuteControllers,
+    ExecuteMergedTrajectory, ExecuteTrajectory, SynchronizedExecution,
+};
+use rust_axum_ros2::models::execution::{Execution, ExecutionEvent, GoalEvent};
+use rust_axum_ros2::models::joint_state::JointState;
+use rust_axum_ros2::models::motion::{MoveJoints, MoveJointsExecution, MoveJointsQueued};
+use rust_axum_ros2::models::page::{Page, Pagination};
+use rust_axum_ros2::models::queue::{GoalPolicy, GoalPolicyBody, QueuedGoal, ReorderQueue};
+use rust_axum_ros2::models::robot::{LoadRobotModel, RobotModel, UrdfSource};
+use rust_axum_ros2::models::service::{CallService, ServiceResponse};
+use rust_axum_ros2::models::task::{CreateTask, ExecuteTask, Task, TaskExecution, TaskQueued};
+use rust_axum_ros2::models::timeout::{ExecutionTimeouts, TimeoutOverrides};
+use rust_axum_ros2::models::topic::{
+    PublishMessage, PublishedMessage, TopicBridgeStatus, TopicMessage,
+};
+use rust_axum_ros2::models::trajectory::{CompleteCondition, JointLimits, JointTrajectory};
 use rust_axum_ros2::models::user::{CreateUser, User};
+use rust_axum_ros2::trajectory::Submission;
 use serde_json::json;
-use tokio::sync::{mpsc, oneshot};
+use std::convert::Infallible;
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::time::timeout;
+use uuid::Uuid;
 
 #[derive(Debug)]
 enum GatewayCommand {
@@ -18,26 +51,369 @@ enum GatewayCommand {
     //     key: String,
     //     resp: Responder<Option<Bytes>>,
     // },
-    PublishUser { user: User, resp: Responder<()> },
-    PublishTask { task: Task, resp: Responder<()> },
-    ExecuteTask { task: Task, resp: Responder<()> },
-    CancelTask { task: Task, resp: Responder<()> },
+    CreateUser {
+        username: String,
+        resp: Responder<User>,
+    },
+    GetUser {
+        id: u64,
+        resp: Responder<User>,
+    },
+    ListUsers {
+        pagination: Pagination,
+        resp: Responder<Page<User>>,
+    },
+    UpdateUser {
+        id: u64,
+        username: String,
+        resp: Responder<User>,
+    },
+    DeleteUser {
+        id: u64,
+        resp: Responder<User>,
+    },
+    CreateTask {
+        taskname: String,
+        resp: Responder<Task>,
+    },
+    FindOrCreateTask {
+        taskname: String,
+        resp: Responder<Task>,
+    },
+    GetTask {
+        id: u64,
+        resp: Responder<Task>,
+    },
+    ListTasks {
+        pagination: Pagination,
+        resp: Responder<Page<Task>>,
+    },
+    UpdateTask {
+        id: u64,
+        taskname: String,
+        resp: Responder<Task>,
+    },
+    DeleteTask {
+        id: u64,
+        resp: Responder<Task>,
+    },
+    PublishUser {
+        user: User,
+        resp: Responder<()>,
+    },
+    PublishTask {
+        task: Task,
+        resp: Responder<()>,
+    },
+    ExecuteTask {
+        task: Task,
+        trajectory: JointTrajectory,
+        complete_condition: Option<CompleteCondition>,
+        timeouts: TimeoutOverrides,
+        resp: Responder<TaskSubmission>,
+    },
+    ExecuteTrajectory {
+        controller: String,
+        request: ExecuteTrajectory,
+        resp: Responder<TaskSubmission>,
+    },
+    ExecuteControllers {
+        request: ExecuteControllers,
+        resp: Responder<SynchronizedExecution>,
+    },
+    ExecuteMerged {
+        request: ExecuteMergedTrajectory,
+        resp: Responder<CombinedExecution>,
+    },
+    GetCombinedExecution {
+        id: Uuid,
+        resp: Responder<CombinedExecution>,
+    },
+    CancelController {
+        controller: String,
+        resp: Responder<()>,
+    },
+    Shutdown {
+        resp: Responder<()>,
+    },
+    ListControllers {
+        resp: Responder<Vec<ControllerStatus>>,
+    },
+    GetControllerStatus {
+        controller: String,
+        resp: Responder<ControllerStatus>,
+    },
+    MoveJoints {
+        request: MoveJoints,
+        resp: Responder<(JointTrajectory, TaskSubmission)>,
+    },
+    CancelTask {
+        taskname: String,
+        resp: Responder<()>,
+    },
+    GetExecution {
+        goal_id: Uuid,
+        resp: Responder<Option<Execution>>,
+    },
+    CancelExecution {
+        goal_id: Uuid,
+        resp: Responder<()>,
+    },
+    SubscribeExecution {
+        goal_id: Uuid,
+        resp: Responder<Option<broadcast::Receiver<ExecutionEvent>>>,
+    },
+    SubscribeGoalEvents {
+        resp: Responder<broadcast::Receiver<GoalEvent>>,
+    },
+    GetExecutionTimeouts {
+        controller: Option<String>,
+        resp: Responder<ExecutionTimeouts>,
+    },
+    SetExecutionTimeouts {
+        controller: Option<String>,
+        timeouts: ExecutionTimeouts,
+        resp: Responder<()>,
+    },
+    GetJointLimits {
+        controller: Option<String>,
+        resp: Responder<JointLimits>,
+    },
+    SetJointLimits {
+        controller: Option<String>,
+        joint_limits: JointLimits,
+        resp: Responder<()>,
+    },
+    GetJointStates {
+        resp: Responder<Vec<JointState>>,
+    },
+    GetJointState {
+        name: String,
+        resp: Responder<JointState>,
+    },
+    ListTopics {
+        resp: Responder<Vec<TopicBridgeStatus>>,
+    },
+    GetTopicMessage {
+        name: String,
+        resp: Responder<TopicMessage>,
+    },
+    SubscribeTopic {
+        name: String,
+        resp: Responder<broadcast::Receiver<TopicMessage>>,
+    },
+    PublishTopic {
+        name: String,
+        request: PublishMessage,
+        resp: Responder<PublishedMessage>,
+    },
+    CallService {
+        name: String,
+        request: CallService,
+        resp: Responder<ServiceResponse>,
+    },
+    ListActions {
+        resp: Responder<Vec<ActionStatus>>,
+    },
+    SendActionGoal {
+        name: String,
+        request: SendActionGoal,
+        resp: Responder<Uuid>,
+    },
+    GetActionGoal {
+        name: String,
+        goal_id: Uuid,
+        resp: Responder<Execution>,
+    },
+    CancelActionGoal {
+        name: String,
+        goal_id: Uuid,
+        resp: Responder<()>,
+    },
+    SubscribeActionGoal {
+        name: String,
+        goal_id: Uuid,
+        resp: Responder<Option<broadcast::Receiver<ExecutionEvent>>>,
+    },
+    GetRobotModel {
+        resp: Responder<Option<RobotModel>>,
+    },
+    LoadRobotModel {
+        source: UrdfSource,
+        joint_names: Option<Vec<String>>,
+        resp: Responder<RobotModel>,
+    },
+    GetQueue {
+        controller: Option<String>,
+        resp: Responder<Vec<QueuedGoal>>,
+    },
+    ReorderQueue {
+        controller: Option<String>,
+        order: Vec<Uuid>,
+        resp: Responder<Vec<QueuedGoal>>,
+    },
+    RemoveQueuedGoal {
+        controller: Option<String>,
+        queue_id: Uuid,
+        resp: Responder<QueuedGoal>,
+    },
+    ClearQueue {
+        controller: Option<String>,
+        resp: Responder<Vec<QueuedGoal>>,
+    },
+    GetGoalPolicy {
+        controller: Option<String>,
+        resp: Responder<GoalPolicy>,
+    },
+    SetGoalPolicy {
+        controller: Option<String>,
+        policy: GoalPolicy,
+        resp: Responder<()>,
+    },
+}
+
+/// Outcome of `ExecuteTask`: the goal was accepted by the action server or
+/// is waiting in the executor queue.
+#[derive(Debug)]
+enum TaskSubmission {
+    Accepted(Uuid),
+    Queued(QueuedGoal),
 }
 
-type Responder<T> = oneshot::Sender<r2r::Result<T>>;
+type Responder<T> = oneshot::Sender<Result<T, Error>>;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    setup_logger()?;
+async fn main() -> ExitCode {
+    let command = Cli::parse_args().command();
+    let config = match &command {
+        Command::CheckConfig(args) => return cli::check_config(args),
+        Command::Serve(args) => args.load(),
+        Command::SendTrajectory(args) => args.config.load(),
+        Command::Cancel(args) => args.config.load(),
+    };
+    let config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = setup_logger(&config.logger) {
+        eprintln!("failed to set up the logger: {}", e);
+        return ExitCode::FAILURE;
+    }
+
+    let gateway = match Gateway::new(&config) {
+        Ok(gateway) => gateway,
+        Err(e) => {
+            log::error!("failed to create the gateway: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // trajectories sent before the model is loaded are not checked against
+    // the joint limits
+    if let Some(robot_model) = &config.robot_model {
+        let res = match gateway
+            .load_robot_model(robot_model.source.clone(), robot_model.joint_names.clone())
+        {
+            Ok(handler) => handler
+                .await
+                .unwrap_or_else(|e| Err(Error::Other(e.into()))),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = res {
+            log::error!("failed to load the robot model: {}", e);
+        }
+    }
+
+    // validated with the config
+    let shutdown_timeout = config.server.shutdown_timeout().unwrap();
+    let code = match command {
+        Command::Serve(_) => match serve(gateway, config, shutdown_timeout).await {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                log::error!("server failed: {}", e);
+                ExitCode::FAILURE
+            }
+        },
+        Command::SendTrajectory(args) => {
+            cli::send_trajectory(&gateway, &args, shutdown_timeout).await
+        }
+        Command::Cancel(args) => cli::cancel(&gateway, &args).await,
+        Command::CheckConfig(_) => unreachable!(),
+    };
+    log::logger().flush();
+    code
+}
 
-    let gateway = Gateway::new("rust_axum_ros2_node", "")?;
+async fn serve(
+    gateway: Gateway,
+    config: Config,
+    shutdown_timeout: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
     // let arc_gateway = Arc::new(Mutex::new(gateway));
 
-    let (tx, mut rx) = mpsc::channel(2);
+    let (tx, mut rx) = mpsc::channel(config.server.command_channel_capacity);
 
     let manager = tokio::spawn(async move {
         while let Some(cmd) = rx.recv().await {
             match cmd {
+                GatewayCommand::CreateUser { username, resp } => {
+                    log::info!("CreateUser: {}", username);
+                    let res = gateway.create_record::<User>(&username);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::GetUser { id, resp } => {
+                    log::info!("GetUser: {}", id);
+                    let res = gateway.get_record::<User>(id);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::ListUsers { pagination, resp } => {
+                    log::info!("ListUsers: {:?}", pagination);
+                    let res = gateway.list_records::<User>(&pagination);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::UpdateUser { id, username, resp } => {
+                    log::info!("UpdateUser: {} {}", id, username);
+                    let res = gateway.update_record::<User>(id, &username);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::DeleteUser { id, resp } => {
+                    log::info!("DeleteUser: {}", id);
+                    let res = gateway.delete_record::<User>(id);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::CreateTask { taskname, resp } => {
+                    log::info!("CreateTask: {}", taskname);
+                    let res = gateway.create_record::<Task>(&taskname);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::FindOrCreateTask { taskname, resp } => {
+                    log::info!("FindOrCreateTask: {}", taskname);
+                    let res = gateway.find_or_create_record::<Task>(&taskname);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::GetTask { id, resp } => {
+                    log::info!("GetTask: {}", id);
+                    let res = gateway.get_record::<Task>(id);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::ListTasks { pagination, resp } => {
+                    log::info!("ListTasks: {:?}", pagination);
+                    let res = gateway.list_records::<Task>(&pagination);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::UpdateTask { id, taskname, resp } => {
+                    log::info!("UpdateTask: {} {}", id, taskname);
+                    let res = gateway.update_record::<Task>(id, &taskname);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::DeleteTask { id, resp } => {
+                    log::info!("DeleteTask: {}", id);
+                    let res = gateway.delete_record::<Task>(id);
+                    let _ = resp.send(res);
+                }
                 GatewayCommand::PublishUser { user, resp } => {
                     log::info!("PublishUser: {:?}", user);
                     let res = gateway.publish_user(user);
@@ -48,29 +424,342 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     let res = gateway.publish_task(task);
                     let _ = resp.send(res);
                 }
-                GatewayCommand::ExecuteTask { task, resp } => {
+                GatewayCommand::ExecuteTask {
+                    task,
+                    trajectory,
+                    complete_condition,
+                    timeouts,
+                    resp,
+                } => {
                     log::info!("ExecuteTask: {:?}", task);
-                    let _res = gateway
-                        .execute_follow_joint_trajectory()
-                        .expect("failed to execute_follow_joint_trajectory");
-
-                    // let _ = res.await;
-                    let _ = resp.send(Ok(()));
+                    let res = gateway.execute_follow_joint_trajectory(
+                        None,
+                        trajectory,
+                        complete_condition,
+                        &timeouts,
+                    );
+                    respond_after_acceptance(res, resp);
                 }
-                GatewayCommand::CancelTask { task, resp } => {
-                    log::info!("CancelTask: {:?}", task);
-                    let res = gateway.cancel_follow_joint_trajectory();
-                    match res {
+                GatewayCommand::ExecuteTrajectory {
+                    controller,
+                    request,
+                    resp,
+                } => {
+                    log::info!("ExecuteTrajectory: {}", controller);
+                    let res = gateway.execute_follow_joint_trajectory(
+                        Some(&controller),
+                        request.trajectory,
+                        request.complete_condition,
+                        &request.timeouts,
+                    );
+                    respond_after_acceptance(res, resp);
+                }
+                GatewayCommand::ExecuteControllers { request, resp } => {
+                    log::info!("ExecuteControllers: {:?}", request.goals.keys());
+                    match gateway.execute_synchronized(request) {
                         Ok(handler) => {
-                            handler.await.unwrap();
+                            tokio::spawn(async move {
+                                let res = handler
+                                    .await
+                                    .unwrap_or_else(|e| Err(Error::Other(e.into())));
+                                let _ = resp.send(res);
+                            });
                         }
                         Err(e) => {
-                            log::error!("CancelTask failed: {:?}", e);
+                            let _ = resp.send(Err(e));
                         }
                     }
-
+                }
+                GatewayCommand::ExecuteMerged { request, resp } => {
+                    log::info!("ExecuteMerged: {:?}", request.trajectory.joint_names);
+                    match gateway.execute_merged(request) {
+                        Ok(handler) => {
+                            tokio::spawn(async move {
+                                let res = handler
+                                    .await
+                                    .unwrap_or_else(|e| Err(Error::Other(e.into())));
+                                let _ = resp.send(res);
+                            });
+                        }
+                        Err(e) => {
+                            let _ = resp.send(Err(e));
+                        }
+                    }
+                }
+                GatewayCommand::GetCombinedExecution { id, resp } => {
+                    log::info!("GetCombinedExecution: {}", id);
+                    let res = gateway.get_combined_execution(&id);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::Shutdown { resp } => {
+                    log::info!("Shutdown");
+                    gateway.shutdown(shutdown_timeout).await;
                     let _ = resp.send(Ok(()));
                 }
+                GatewayCommand::CancelController { controller, resp } => {
+                    log::info!("CancelController: {}", controller);
+                    let res = gateway.cancel_follow_joint_trajectory(Some(&controller));
+                    respond_after_cancel(res, resp);
+                }
+                GatewayCommand::ListControllers { resp } => {
+                    log::info!("ListControllers");
+                    let res = gateway.controllers();
+                    let _ = resp.send(Ok(res));
+                }
+                GatewayCommand::GetControllerStatus { controller, resp } => {
+                    log::info!("GetControllerStatus: {}", controller);
+                    let res = gateway.controller_status(&controller);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::MoveJoints { request, resp } => {
+                    log::info!("MoveJoints: {:?}", request.positions);
+                    match gateway.move_joints(request) {
+                        Ok((trajectory, Submission::Sent(acceptance))) => {
+                            tokio::spawn(async move {
+                                let res = acceptance.await.unwrap_or_else(|_| {
+                                    Err(Error::Other(anyhow::anyhow!("goal request was dropped")))
+                                });
+                                let res = res
+                                    .map(|goal_id| (trajectory, TaskSubmission::Accepted(goal_id)));
+                                let _ = resp.send(res);
+                            });
+                        }
+                        Ok((trajectory, Submission::Queued(queued))) => {
+                            let _ = resp.send(Ok((trajectory, TaskSubmission::Queued(queued))));
+                        }
+                        Err(e) => {
+                            let _ = resp.send(Err(e));
+                        }
+                    }
+                }
+                GatewayCommand::CancelTask { taskname, resp } => {
+                    log::info!("CancelTask: {}", taskname);
+                    let res = gateway.cancel_follow_joint_trajectory(None);
+                    respond_after_cancel(res, resp);
+                }
+                GatewayCommand::GetExecution { goal_id, resp } => {
+                    log::info!("GetExecution: {}", goal_id);
+                    let res = gateway.get_follow_joint_trajectory(&goal_id);
+                    let _ = resp.send(Ok(res));
+                }
+                GatewayCommand::CancelExecution { goal_id, resp } => {
+                    log::info!("CancelExecution: {}", goal_id);
+                    let res = gateway.cancel_follow_joint_trajectory_goal(&goal_id);
+                    respond_after_cancel(res, resp);
+                }
+                GatewayCommand::SubscribeExecution { goal_id, resp } => {
+                    log::info!("SubscribeExecution: {}", goal_id);
+                    let res = gateway.subscribe_follow_joint_trajectory(&goal_id);
+                    let _ = resp.send(Ok(res));
+                }
+                GatewayCommand::SubscribeGoalEvents { resp } => {
+                    log::info!("SubscribeGoalEvents");
+                    let res = gateway.subscribe_goal_events();
+                    let _ = resp.send(Ok(res));
+                }
+                GatewayCommand::GetExecutionTimeouts { controller, resp } => {
+                    log::info!("GetExecutionTimeouts: {:?}", controller);
+                    let res = gateway.execution_timeouts(controller.as_deref());
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::SetExecutionTimeouts {
+                    controller,
+                    timeouts,
+                    resp,
+                } => {
+                    log::info!("SetExecutionTimeouts: {:?} {:?}", controller, timeouts);
+                    let res = gateway.set_execution_timeouts(controller.as_deref(), timeouts);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::GetJointLimits { controller, resp } => {
+                    log::info!("GetJointLimits: {:?}", controller);
+                    let res = gateway.joint_limits(controller.as_deref());
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::SetJointLimits {
+                    controller,
+                    joint_limits,
+                    resp,
+                } => {
+                    log::info!("SetJointLimits: {:?} {:?}", controller, joint_limits);
+                    let res = gateway.set_joint_limits(controller.as_deref(), joint_limits);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::GetJointStates { resp } => {
+                    log::debug!("GetJointStates");
+                    let res = gateway.joint_states();
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::GetJointState { name, resp } => {
+                    log::debug!("GetJointState: {}", name);
+                    let res = gateway.joint_state(&name);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::ListTopics { resp } => {
+                    log::debug!("ListTopics");
+                    let res = gateway.topics();
+                    let _ = resp.send(Ok(res));
+                }
+                GatewayCommand::GetTopicMessage { name, resp } => {
+                    log::debug!("GetTopicMessage: {}", name);
+                    let res = gateway.topic_message(&name);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::SubscribeTopic { name, resp } => {
+                    log::info!("SubscribeTopic: {}", name);
+                    let res = gateway.subscribe_topic(&name);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::PublishTopic {
+                    name,
+                    request,
+                    resp,
+                } => {
+                    log::info!("PublishTopic: {} {}", name, request.msg_type);
+                    let res = gateway.publish_topic(&name, request);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::CallService {
+                    name,
+                    request,
+                    resp,
+                } => {
+                    log::info!("CallService: {} {}", name, request.srv_type);
+                    match gateway.call_service(&name, request) {
+                        Ok(handler) => {
+                            tokio::spawn(async move {
+                                let res = handler
+                                    .await
+                                    .unwrap_or_else(|e| Err(Error::Other(e.into())));
+                                let _ = resp.send(res);
+                            });
+                        }
+                        Err(e) => {
+                            let _ = resp.send(Err(e));
+                        }
+                    }
+                }
+                GatewayCommand::ListActions { resp } => {
+                    log::debug!("ListActions");
+                    let res = gateway.actions();
+                    let _ = resp.send(Ok(res));
+                }
+                GatewayCommand::SendActionGoal {
+                    name,
+                    request,
+                    resp,
+                } => {
+                    log::info!("SendActionGoal: {}", name);
+                    match gateway.send_action_goal(&name, request) {
+                        Ok(acceptance) => {
+                            // do not block other commands while the action server decides
+                            tokio::spawn(async move {
+                                let res = acceptance.await.unwrap_or_else(|_| {
+                                    Err(Error::Other(anyhow::anyhow!("goal request was dropped")))
+                                });
+                                let _ = resp.send(res);
+                            });
+                        }
+                        Err(e) => {
+                            let _ = resp.send(Err(e));
+                        }
+                    }
+                }
+                GatewayCommand::GetActionGoal {
+                    name,
+                    goal_id,
+                    resp,
+                } => {
+                    log::info!("GetActionGoal: {} {}", name, goal_id);
+                    let res = gateway.get_action_goal(&name, &goal_id);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::CancelActionGoal {
+                    name,
+                    goal_id,
+                    resp,
+                } => {
+                    log::info!("CancelActionGoal: {} {}", name, goal_id);
+                    let res = gateway.cancel_action_goal(&name, &goal_id);
+                    respond_after_cancel(res, resp);
+                }
+                GatewayCommand::SubscribeActionGoal {
+                    name,
+                    goal_id,
+                    resp,
+                } => {
+                    log::info!("SubscribeActionGoal: {} {}", name, goal_id);
+                    let res = gateway.subscribe_action_goal(&name, &goal_id);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::GetRobotModel { resp } => {
+                    log::info!("GetRobotModel");
+                    let res = gateway.robot_model();
+                    let _ = resp.send(Ok(res));
+                }
+                GatewayCommand::LoadRobotModel {
+                    source,
+                    joint_names,
+                    resp,
+                } => {
+                    log::info!("LoadRobotModel: {:?}", source);
+                    match gateway.load_robot_model(source, joint_names) {
+                        // loading from a topic waits for the publisher
+                        Ok(handler) => {
+                            tokio::spawn(async move {
+                                let res = handler
+                                    .await
+                                    .unwrap_or_else(|e| Err(Error::Other(e.into())));
+                                let _ = resp.send(res);
+                            });
+                        }
+                        Err(e) => {
+                            let _ = resp.send(Err(e));
+                        }
+                    }
+                }
+                GatewayCommand::GetQueue { controller, resp } => {
+                    log::info!("GetQueue: {:?}", controller);
+                    let res = gateway.queued_goals(controller.as_deref());
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::ReorderQueue {
+                    controller,
+                    order,
+                    resp,
+                } => {
+                    log::info!("ReorderQueue: {:?} {:?}", controller, order);
+                    let res = gateway.reorder_queue(controller.as_deref(), &order);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::RemoveQueuedGoal {
+                    controller,
+                    queue_id,
+                    resp,
+                } => {
+                    log::info!("RemoveQueuedGoal: {:?} {}", controller, queue_id);
+                    let res = gateway.remove_queued_goal(controller.as_deref(), &queue_id);
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::ClearQueue { controller, resp } => {
+                    log::info!("ClearQueue: {:?}", controller);
+                    let res = gateway.clear_queue(controller.as_deref());
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::GetGoalPolicy { controller, resp } => {
+                    log::info!("GetGoalPolicy: {:?}", controller);
+                    let res = gateway.goal_policy(controller.as_deref());
+                    let _ = resp.send(res);
+                }
+                GatewayCommand::SetGoalPolicy {
+                    controller,
+                    policy,
+                    resp,
+                } => {
+                    log::info!("SetGoalPolicy: {:?} {:?}", controller, policy);
+                    let res = gateway.set_goal_policy(controller.as_deref(), policy);
+                    let _ = resp.send(res);
+                }
             }
         }
     });
@@ -81,20 +770,161 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/hello/:name", get(json_hello))
         .route("/user", post(create_user))
         .route("/task", post(create_task))
+        .route("/users", get(list_users).post(create_user))
+        .route(
+            "/users/:id",
+            get(get_user).put(update_user).delete(delete_user),
+        )
+        .route("/tasks", get(list_tasks).post(create_task))
+        .route(
+            "/tasks/:id",
+            get(get_task).put(update_task).delete(delete_task),
+        )
         .route("/execute_task", post(execute_task))
+        .route("/move_joints", post(move_joints))
+        .route("/controllers", get(list_controllers))
+        .route("/controllers/execute", post(execute_controllers))
+        .route("/controllers/:name/execute", post(execute_on_controller))
+        .route("/controllers/:name/cancel", post(cancel_controller))
+        .route("/controllers/:name/status", get(get_controller_status))
+        .route("/trajectories", post(execute_merged))
+        .route("/trajectories/:id", get(get_combined_execution))
         .route("/cancel_task", post(cancel_task))
+        .route(
+            "/executions/:goal_id",
+            get(get_execution).delete(cancel_execution),
+        )
+        .route("/executions/:goal_id/cancel", post(cancel_execution))
+        .route("/ws/execution/:goal_id", get(execution_ws))
+        .route("/events", get(goal_events_sse))
+        .route(
+            "/execution_timeouts",
+            get(get_execution_timeouts).put(set_execution_timeouts),
+        )
+        .route("/joint_limits", get(get_joint_limits).put(set_joint_limits))
+        .route("/joint_states", get(get_joint_states))
+        .route("/joint_states/:joint", get(get_joint_state))
+        .route("/topics", get(list_topics))
+        .route("/topics/:name", get(get_topic_message))
+        .route("/topics/:name/publish", post(publish_topic))
+        .route("/ws/topics/:name", get(topic_ws))
+        .route("/services/:name/call", post(call_service))
+        .route("/actions", get(list_actions))
+        .route("/actions/:name/goals", post(send_action_goal))
+        .route("/actions/:name/goals/:goal_id", get(get_action_goal))
+        .route(
+            "/actions/:name/goals/:goal_id/cancel",
+            post(cancel_action_goal),
+        )
+        .route("/ws/actions/:name/goals/:goal_id", get(action_goal_ws))
+        .route("/robot_model", get(get_robot_model).post(load_robot_model))
+        .route(
+            "/queue",
+            get(get_queue).put(reorder_queue).delete(clear_queue),
+        )
+        .route("/queue/policy", get(get_goal_policy).put(set_goal_policy))
+        .route("/queue/:queue_id", delete(remove_queued_goal))
         .with_state(tx.clone());
 
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+    let listener = tokio::net::TcpListener::bind(config.server.bind).await?;
+
+    log::info!("Server running on http://{}", config.server.bind);
+    let (stop_tx, stop_rx) = oneshot::channel::<()>();
+    let mut server = tokio::spawn(async move {
+        axum::serve(listener, app)
+            .with_graceful_shutdown(async {
+                let _ = stop_rx.await;
+            })
+            .await
+    });
+
+    tokio::select! {
+        res = &mut server => {
+            res??;
+            return Ok(());
+        }
+        _ = cli::shutdown_signal() => {}
+    }
 
-    log::info!("Server running on http://localhost:3000");
-    axum::serve(listener, app).await.unwrap();
+    // stop accepting requests, then stop the robot before waiting for the
+    // open connections, which SSE and WebSocket clients may keep forever
+    let _ = stop_tx.send(());
+    let (resp_tx, resp_rx) = oneshot::channel();
+    tx.send(GatewayCommand::Shutdown { resp: resp_tx }).await?;
+    let _ = resp_rx.await;
+    drop(tx);
 
-    manager.await.unwrap();
+    match timeout(shutdown_timeout, &mut server).await {
+        Ok(res) => res??,
+        Err(_) => {
+            log::warn!(
+                "closing the connections still open after {:?}",
+                shutdown_timeout
+            );
+            server.abort();
+        }
+    }
+    // the gateway has been shut down, but connections of an aborted server
+    // may still hold senders
+    manager.abort();
+    log::info!("Server stopped");
 
     Ok(())
 }
 
+/// Sends a command to the gateway manager and waits for its reply.
+async fn request<T>(
+    tx: &mpsc::Sender<GatewayCommand>,
+    cmd: impl FnOnce(Responder<T>) -> GatewayCommand,
+) -> Result<T, Error> {
+    let (resp_tx, resp_rx) = oneshot::channel();
+    tx.send(cmd(resp_tx))
+        .await
+        .map_err(|_| Error::GatewayUnavailable)?;
+    resp_rx.await.map_err(|_| Error::GatewayUnavailable)?
+}
+
+/// Replies to `resp` once the action server accepted or rejected the goal.
+fn respond_after_acceptance(res: Result<Submission, Error>, resp: Responder<TaskSubmission>) {
+    match res {
+        Ok(Submission::Sent(acceptance)) => {
+            // do not block other commands while the action server decides
+            tokio::spawn(async move {
+                let res = acceptance.await.unwrap_or_else(|_| {
+                    Err(Error::Other(anyhow::anyhow!("goal request was dropped")))
+                });
+                let _ = resp.send(res.map(TaskSubmission::Accepted));
+            });
+        }
+        Ok(Submission::Queued(queued)) => {
+            let _ = resp.send(Ok(TaskSubmission::Queued(queued)));
+        }
+        Err(e) => {
+            let _ = resp.send(Err(e));
+        }
+    }
+}
+
+/// Replies to `resp` once the action server answered the cancel request.
+fn respond_after_cancel(
+    res: Result<tokio::task::JoinHandle<Result<(), Error>>, Error>,
+    resp: Responder<()>,
+) {
+    match res {
+        Ok(handler) => {
+            tokio::spawn(async move {
+                let res = handler
+                    .await
+                    .unwrap_or_else(|e| Err(Error::Other(e.into())));
+                let _ = resp.send(res);
+            });
+        }
+        Err(e) => {
+            let _ = resp.send(Err(e));
+        }
+    }
+}
+
 async fn root() -> &'static str {
     log::info!("Hello, world!");
     "Hello, World!"
@@ -111,21 +941,34 @@ async fn create_user(
     State(tx): State<mpsc::Sender<GatewayCommand>>,
     Json(payload): Json<CreateUser>,
 ) -> impl IntoResponse {
-    let user = User::new(1111, payload.username.clone());
-
-    let (resp_tx, resp_rx) = oneshot::channel();
-    let cmd = GatewayCommand::PublishUser {
-        user: user.clone(),
-        resp: resp_tx,
+    let res = request(&tx, |resp| GatewayCommand::CreateUser {
+        username: payload.username,
+        resp,
+    })
+    .await;
+    let user = match res {
+        Ok(user) => user,
+        Err(e) => {
+            log::info!("Error creating user: {:?}", e);
+            return e.into_response();
+        }
     };
-    tx.send(cmd).await.unwrap();
 
-    let res = resp_rx.await.unwrap();
+    let res = request(&tx, |resp| GatewayCommand::PublishUser {
+        user: user.clone(),
+        resp,
+    })
+    .await;
     match res {
-        Ok(_) => (StatusCode::CREATED, Json(user)),
+        Ok(_) => (
+            StatusCode::CREATED,
+            [(header::LOCATION, format!("/users/{}", user.id()))],
+            Json(user),
+        )
+            .into_response(),
         Err(e) => {
             log::info!("Error publishing user: {:?}", e);
-            (StatusCode::BAD_REQUEST, Json(user))
+            e.into_response()
         }
     }
 }
@@ -134,65 +977,983 @@ async fn create_task(
     State(tx): State<mpsc::Sender<GatewayCommand>>,
     Json(payload): Json<CreateTask>,
 ) -> impl IntoResponse {
-    let task = Task::new(2222, payload.taskname.clone());
-
-    let (resp_tx, resp_rx) = oneshot::channel();
-    let cmd = GatewayCommand::PublishTask {
-        task: task.clone(),
-        resp: resp_tx,
+    let res = request(&tx, |resp| GatewayCommand::CreateTask {
+        taskname: payload.taskname,
+        resp,
+    })
+    .await;
+    let task = match res {
+        Ok(task) => task,
+        Err(e) => {
+            log::info!("Error creating task: {:?}", e);
+            return e.into_response();
+        }
     };
-    tx.send(cmd).await.unwrap();
 
-    let res = resp_rx.await.unwrap();
+    let res = request(&tx, |resp| GatewayCommand::PublishTask {
+        task: task.clone(),
+        resp,
+    })
+    .await;
     match res {
-        Ok(_) => (StatusCode::CREATED, Json(task)),
+        Ok(_) => (
+            StatusCode::CREATED,
+            [(header::LOCATION, format!("/tasks/{}", task.id()))],
+            Json(task),
+        )
+            .into_response(),
         Err(e) => {
             log::info!("Error publishing task: {:?}", e);
-            (StatusCode::BAD_REQUEST, Json(task))
+            e.into_response()
         }
     }
 }
 
-async fn execute_task(
+async fn list_users(
     State(tx): State<mpsc::Sender<GatewayCommand>>,
-    Json(payload): Json<CreateTask>,
+    Query(pagination): Query<Pagination>,
 ) -> impl IntoResponse {
-    let task = Task::new(2222, payload.taskname.clone());
-    let (resp_tx, resp_rx) = oneshot::channel();
-    let cmd = GatewayCommand::ExecuteTask {
-        task: task.clone(),
-        resp: resp_tx,
-    };
-    tx.send(cmd).await.unwrap();
+    let res = request(&tx, |resp| GatewayCommand::ListUsers { pagination, resp }).await;
+    match res {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
+        Err(e) => {
+            log::info!("Error listing users: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn get_user(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(id): Path<u64>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetUser { id, resp }).await;
+    match res {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => {
+            log::info!("Error getting user {}: {:?}", id, e);
+            e.into_response()
+        }
+    }
+}
 
-    let res = resp_rx.await.unwrap();
+async fn update_user(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(id): Path<u64>,
+    Json(payload): Json<CreateUser>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::UpdateUser {
+        id,
+        username: payload.username,
+        resp,
+    })
+    .await;
     match res {
-        Ok(_) => (StatusCode::CREATED, Json(task)),
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
         Err(e) => {
-            log::info!("Error executing task: {:?}", e);
-            (StatusCode::BAD_REQUEST, Json(task))
+            log::info!("Error updating user {}: {:?}", id, e);
+            e.into_response()
         }
     }
 }
 
-async fn cancel_task(
+async fn delete_user(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(id): Path<u64>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::DeleteUser { id, resp }).await;
+    match res {
+        Ok(user) => (StatusCode::OK, Json(user)).into_response(),
+        Err(e) => {
+            log::info!("Error deleting user {}: {:?}", id, e);
+            e.into_response()
+        }
+    }
+}
+
+async fn list_tasks(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Query(pagination): Query<Pagination>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::ListTasks { pagination, resp }).await;
+    match res {
+        Ok(page) => (StatusCode::OK, Json(page)).into_response(),
+        Err(e) => {
+            log::info!("Error listing tasks: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn get_task(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(id): Path<u64>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetTask { id, resp }).await;
+    match res {
+        Ok(task) => (StatusCode::OK, Json(task)).into_response(),
+        Err(e) => {
+            log::info!("Error getting task {}: {:?}", id, e);
+            e.into_response()
+        }
+    }
+}
+
+async fn update_task(
     State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(id): Path<u64>,
     Json(payload): Json<CreateTask>,
 ) -> impl IntoResponse {
-    let task = Task::new(2222, payload.taskname.clone());
-    let (resp_tx, resp_rx) = oneshot::channel();
-    let cmd = GatewayCommand::CancelTask {
-        task: task.clone(),
-        resp: resp_tx,
+    let res = request(&tx, |resp| GatewayCommand::UpdateTask {
+        id,
+        taskname: payload.taskname,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(task) => (StatusCode::OK, Json(task)).into_response(),
+        Err(e) => {
+            log::info!("Error updating task {}: {:?}", id, e);
+            e.into_response()
+        }
+    }
+}
+
+async fn delete_task(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(id): Path<u64>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::DeleteTask { id, resp }).await;
+    match res {
+        Ok(task) => (StatusCode::OK, Json(task)).into_response(),
+        Err(e) => {
+            log::info!("Error deleting task {}: {:?}", id, e);
+            e.into_response()
+        }
+    }
+}
+
+async fn execute_task(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Json(payload): Json<ExecuteTask>,
+) -> impl IntoResponse {
+    // executing a task again reuses its record
+    let res = request(&tx, |resp| GatewayCommand::FindOrCreateTask {
+        taskname: payload.taskname,
+        resp,
+    })
+    .await;
+    let task = match res {
+        Ok(task) => task,
+        Err(e) => {
+            log::info!("Error finding task: {:?}", e);
+            return e.into_response();
+        }
     };
-    tx.send(cmd).await.unwrap();
+    let res = request(&tx, |resp| GatewayCommand::ExecuteTask {
+        task: task.clone(),
+        trajectory: payload.trajectory,
+        complete_condition: payload.complete_condition,
+        timeouts: payload.timeouts,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(TaskSubmission::Accepted(goal_id)) => (
+            StatusCode::CREATED,
+            [(header::LOCATION, format!("/executions/{}", goal_id))],
+            Json(TaskExecution { task, goal_id }),
+        )
+            .into_response(),
+        Ok(TaskSubmission::Queued(queued)) => {
+            (StatusCode::ACCEPTED, Json(TaskQueued { task, queued })).into_response()
+        }
+        Err(e) => {
+            log::info!("Error executing task: {:?}", e);
+            e.into_response()
+        }
+    }
+}
 
-    let res = resp_rx.await.unwrap();
+async fn move_joints(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Json(payload): Json<MoveJoints>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::MoveJoints {
+        request: payload,
+        resp,
+    })
+    .await;
     match res {
-        Ok(_) => (StatusCode::ACCEPTED, Json(task)),
+        Ok((trajectory, TaskSubmission::Accepted(goal_id))) => (
+            StatusCode::CREATED,
+            [(header::LOCATION, format!("/executions/{}", goal_id))],
+            Json(MoveJointsExecution {
+                goal_id,
+                trajectory,
+            }),
+        )
+            .into_response(),
+        Ok((trajectory, TaskSubmission::Queued(queued))) => (
+            StatusCode::ACCEPTED,
+            Json(MoveJointsQueued { queued, trajectory }),
+        )
+            .into_response(),
         Err(e) => {
-            log::info!("Error canceling task: {:?}", e);
-            (StatusCode::BAD_REQUEST, Json(task))
+            log::info!("Error moving joints: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn list_controllers(State(tx): State<mpsc::Sender<GatewayCommand>>) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::ListControllers { resp }).await;
+    match res {
+        Ok(controllers) => (StatusCode::OK, Json(controllers)).into_response(),
+        Err(e) => {
+            log::info!("Error listing controllers: {:?}", e);
+            e.into_response()
         }
     }
 }
+
+async fn get_controller_status(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetControllerStatus {
+        controller: name,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(status) => (StatusCode::OK, Json(status)).into_response(),
+        Err(e) => {
+            log::info!("Error getting controller status: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn execute_on_controller(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(name): Path<String>,
+    Json(payload): Json<ExecuteTrajectory>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::ExecuteTrajectory {
+        controller: name.clone(),
+        request: payload,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(TaskSubmission::Accepted(goal_id)) => (
+            StatusCode::CREATED,
+            [(header::LOCATION, format!("/executions/{}", goal_id))],
+            Json(json!({ "controller": name, "goal_id": goal_id })),
+        )
+            .into_response(),
+        Ok(TaskSubmission::Queued(queued)) => (StatusCode::ACCEPTED, Json(queued)).into_response(),
+        Err(e) => {
+            log::info!("Error executing trajectory on {}: {:?}", name, e);
+            e.into_response()
+        }
+    }
+}
+
+async fn execute_controllers(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Json(payload): Json<ExecuteControllers>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::ExecuteControllers {
+        request: payload,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(execution) => (StatusCode::CREATED, Json(execution)).into_response(),
+        Err(e) => {
+            log::info!("Error executing synchronized trajectories: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn execute_merged(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Json(payload): Json<ExecuteMergedTrajectory>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::ExecuteMerged {
+        request: payload,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(combined) => (
+            StatusCode::CREATED,
+            [(header::LOCATION, format!("/trajectories/{}", combined.id))],
+            Json(combined),
+        )
+            .into_response(),
+        Err(e) => {
+            log::info!("Error executing merged trajectory: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn get_combined_execution(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetCombinedExecution {
+        id,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(combined) => (StatusCode::OK, Json(combined)).into_response(),
+        Err(e) => {
+            log::info!("Error getting merged trajectory {}: {:?}", id, e);
+            e.into_response()
+        }
+    }
+}
+
+async fn cancel_controller(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::CancelController {
+        controller: name.clone(),
+        resp,
+    })
+    .await;
+    match res {
+        Ok(_) => (StatusCode::ACCEPTED, Json(json!({ "controller": name }))).into_response(),
+        Err(e) => {
+            log::info!("Error canceling controller {}: {:?}", name, e);
+            e.into_response()
+        }
+    }
+}
+
+async fn get_execution(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(goal_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetExecution { goal_id, resp }).await;
+    match res {
+        Ok(Some(execution)) => (StatusCode::OK, Json(execution)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": format!("unknown goal {}", goal_id) })),
+        )
+            .into_response(),
+        Err(e) => {
+            log::info!("Error getting execution: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn cancel_task(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Json(payload): Json<CreateTask>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::CancelTask {
+        taskname: payload.taskname.clone(),
+        resp,
+    })
+    .await;
+    match res {
+        Ok(_) => (
+            StatusCode::ACCEPTED,
+            Json(json!({ "taskname": payload.taskname })),
+        )
+            .into_response(),
+        Err(e) => {
+            log::info!("Error canceling task: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn cancel_execution(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(goal_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::CancelExecution {
+        goal_id,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(_) => (StatusCode::ACCEPTED, Json(json!({ "goal_id": goal_id }))).into_response(),
+        Err(e) => {
+            log::info!("Error canceling execution: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn get_execution_timeouts(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Query(query): Query<ControllerQuery>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetExecutionTimeouts {
+        controller: query.controller,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(timeouts) => (StatusCode::OK, Json(timeouts)).into_response(),
+        Err(e) => {
+            log::info!("Error getting execution timeouts: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn set_execution_timeouts(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Query(query): Query<ControllerQuery>,
+    Json(payload): Json<ExecutionTimeouts>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::SetExecutionTimeouts {
+        controller: query.controller,
+        timeouts: payload,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(_) => (StatusCode::OK, Json(payload)).into_response(),
+        Err(e) => {
+            log::info!("Error setting execution timeouts: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn get_joint_limits(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Query(query): Query<ControllerQuery>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetJointLimits {
+        controller: query.controller,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(joint_limits) => (StatusCode::OK, Json(joint_limits)).into_response(),
+        Err(e) => {
+            log::info!("Error getting joint limits: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn set_joint_limits(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Query(query): Query<ControllerQuery>,
+    Json(payload): Json<JointLimits>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::SetJointLimits {
+        controller: query.controller,
+        joint_limits: payload.clone(),
+        resp,
+    })
+    .await;
+    match res {
+        Ok(_) => (StatusCode::OK, Json(payload)).into_response(),
+        Err(e) => {
+            log::info!("Error setting joint limits: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn get_joint_states(State(tx): State<mpsc::Sender<GatewayCommand>>) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetJointStates { resp }).await;
+    match res {
+        Ok(joint_states) => (StatusCode::OK, Json(joint_states)).into_response(),
+        Err(e) => {
+            log::info!("Error getting joint states: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn get_joint_state(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(joint): Path<String>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetJointState {
+        name: joint,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(joint_state) => (StatusCode::OK, Json(joint_state)).into_response(),
+        Err(e) => {
+            log::info!("Error getting joint state: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn list_topics(State(tx): State<mpsc::Sender<GatewayCommand>>) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::ListTopics { resp }).await;
+    match res {
+        Ok(topics) => (StatusCode::OK, Json(topics)).into_response(),
+        Err(e) => {
+            log::info!("Error listing topics: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn get_topic_message(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetTopicMessage { name, resp }).await;
+    match res {
+        Ok(msg) => (StatusCode::OK, Json(msg)).into_response(),
+        Err(e) => {
+            log::info!("Error getting topic message: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn publish_topic(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(name): Path<String>,
+    Json(payload): Json<PublishMessage>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::PublishTopic {
+        name,
+        request: payload,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(published) => (StatusCode::OK, Json(published)).into_response(),
+        Err(e) => {
+            log::info!("Error publishing topic: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn call_service(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(name): Path<String>,
+    Json(payload): Json<CallService>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::CallService {
+        name,
+        request: payload,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(e) => {
+            log::info!("Error calling service: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn list_actions(State(tx): State<mpsc::Sender<GatewayCommand>>) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::ListActions { resp }).await;
+    match res {
+        Ok(actions) => (StatusCode::OK, Json(actions)).into_response(),
+        Err(e) => {
+            log::info!("Error listing actions: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn send_action_goal(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(name): Path<String>,
+    Json(payload): Json<SendActionGoal>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::SendActionGoal {
+        name: name.clone(),
+        request: payload,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(goal_id) => (
+            StatusCode::CREATED,
+            [(
+                header::LOCATION,
+                format!("/actions/{}/goals/{}", name, goal_id),
+            )],
+            Json(json!({ "action": name, "goal_id": goal_id })),
+        )
+            .into_response(),
+        Err(e) => {
+            log::info!("Error sending goal to {}: {:?}", name, e);
+            e.into_response()
+        }
+    }
+}
+
+async fn get_action_goal(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path((name, goal_id)): Path<(String, Uuid)>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetActionGoal {
+        name,
+        goal_id,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(execution) => (StatusCode::OK, Json(execution)).into_response(),
+        Err(e) => {
+            log::info!("Error getting action goal: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn cancel_action_goal(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path((name, goal_id)): Path<(String, Uuid)>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::CancelActionGoal {
+        name: name.clone(),
+        goal_id,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(_) => (
+            StatusCode::ACCEPTED,
+            Json(json!({ "action": name, "goal_id": goal_id })),
+        )
+            .into_response(),
+        Err(e) => {
+            log::info!("Error canceling action goal: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn action_goal_ws(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path((name, goal_id)): Path<(String, Uuid)>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::SubscribeActionGoal {
+        name,
+        goal_id,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(Some(events)) => ws
+            .on_upgrade(move |socket| stream_execution(socket, events))
+            .into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": format!("no running goal {}", goal_id) })),
+        )
+            .into_response(),
+        Err(e) => {
+            log::info!("Error subscribing action goal: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn topic_ws(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(name): Path<String>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::SubscribeTopic { name, resp }).await;
+    match res {
+        Ok(messages) => ws
+            .on_upgrade(move |socket| stream_topic(socket, messages))
+            .into_response(),
+        Err(e) => {
+            log::info!("Error subscribing topic: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn stream_topic(mut socket: WebSocket, mut messages: broadcast::Receiver<TopicMessage>) {
+    loop {
+        let msg = match messages.recv().await {
+            Ok(msg) => msg,
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                log::warn!("topic websocket lagged {} messages", n);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        let text = match serde_json::to_string(&msg) {
+            Ok(text) => text,
+            Err(e) => {
+                log::error!("failed to serialize topic message: {:?}", e);
+                continue;
+            }
+        };
+        if socket.send(Message::Text(text)).await.is_err() {
+            // client disconnected
+            return;
+        }
+    }
+
+    let _ = socket.close().await;
+}
+
+async fn get_robot_model(State(tx): State<mpsc::Sender<GatewayCommand>>) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetRobotModel { resp }).await;
+    match res {
+        Ok(Some(robot_model)) => (StatusCode::OK, Json(robot_model)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": "no robot model is loaded" })),
+        )
+            .into_response(),
+        Err(e) => {
+            log::info!("Error getting robot model: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn load_robot_model(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Json(payload): Json<LoadRobotModel>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::LoadRobotModel {
+        source: payload.source,
+        joint_names: payload.joint_names,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(robot_model) => (StatusCode::OK, Json(robot_model)).into_response(),
+        Err(e) => {
+            log::info!("Error loading robot model: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn get_queue(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Query(query): Query<ControllerQuery>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetQueue {
+        controller: query.controller,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(queue) => (StatusCode::OK, Json(queue)).into_response(),
+        Err(e) => {
+            log::info!("Error getting queue: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn reorder_queue(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Query(query): Query<ControllerQuery>,
+    Json(payload): Json<ReorderQueue>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::ReorderQueue {
+        controller: query.controller,
+        order: payload.order,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(queue) => (StatusCode::OK, Json(queue)).into_response(),
+        Err(e) => {
+            log::info!("Error reordering queue: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn clear_queue(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Query(query): Query<ControllerQuery>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::ClearQueue {
+        controller: query.controller,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(removed) => (StatusCode::OK, Json(removed)).into_response(),
+        Err(e) => {
+            log::info!("Error clearing queue: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn remove_queued_goal(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(queue_id): Path<Uuid>,
+    Query(query): Query<ControllerQuery>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::RemoveQueuedGoal {
+        controller: query.controller,
+        queue_id,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(removed) => (StatusCode::OK, Json(removed)).into_response(),
+        Err(e) => {
+            log::info!("Error removing queued goal: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn get_goal_policy(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Query(query): Query<ControllerQuery>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::GetGoalPolicy {
+        controller: query.controller,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(policy) => (StatusCode::OK, Json(GoalPolicyBody { policy })).into_response(),
+        Err(e) => {
+            log::info!("Error getting goal policy: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn set_goal_policy(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Query(query): Query<ControllerQuery>,
+    Json(payload): Json<GoalPolicyBody>,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::SetGoalPolicy {
+        controller: query.controller,
+        policy: payload.policy,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(_) => (StatusCode::OK, Json(payload)).into_response(),
+        Err(e) => {
+            log::info!("Error setting goal policy: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn execution_ws(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+    Path(goal_id): Path<Uuid>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let res = request(&tx, |resp| GatewayCommand::SubscribeExecution {
+        goal_id,
+        resp,
+    })
+    .await;
+    match res {
+        Ok(Some(events)) => ws
+            .on_upgrade(move |socket| stream_execution(socket, events))
+            .into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "message": format!("no running goal {}", goal_id) })),
+        )
+            .into_response(),
+        Err(e) => {
+            log::info!("Error subscribing execution: {:?}", e);
+            e.into_response()
+        }
+    }
+}
+
+async fn stream_execution(mut socket: WebSocket, mut events: broadcast::Receiver<ExecutionEvent>) {
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                log::warn!("execution websocket lagged {} events", n);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        let text = match serde_json::to_string(&event) {
+            Ok(text) => text,
+            Err(e) => {
+                log::error!("failed to serialize execution event: {:?}", e);
+                continue;
+            }
+        };
+        if socket.send(Message::Text(text)).await.is_err() {
+            // client disconnected
+            return;
+        }
+
+        if matches!(event, ExecutionEvent::Result { .. }) {
+            break;
+        }
+    }
+
+    let _ = socket.close().await;
+}
+
+async fn goal_events_sse(
+    State(tx): State<mpsc::Sender<GatewayCommand>>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Error> {
+    let res = request(&tx, |resp| GatewayCommand::SubscribeGoalEvents { resp }).await;
+    let events = match res {
+        Ok(events) => events,
+        Err(e) => {
+            log::info!("Error subscribing goal events: {:?}", e);
+            return Err(e);
+        }
+    };
+
+    let stream = stream::unfold(events, |mut events| async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    let event = Event::default().json_data(&event).unwrap_or_else(|e| {
+                        log::error!("failed to serialize goal event: {:?}", e);
+                        Event::default().comment("serialization error")
+                    });
+                    return Some((Ok(event), events));
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    log::warn!("goal event stream lagged {} events", n);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
@@ -1,2 +1,15 @@
+pub mod action;
+pub mod controller;
+pub mod execution;
+pub mod joint_state;
+pub mod message;
+pub mod motion;
+pub mod page;
+pub mod queue;
+pub mod robot;
+pub mod service;
 pub mod task;
+pub mod timeout;
+pub mod topic;
+pub mod trajectory;
 pub mod user;
@@ -0,0 +1,120 @@
+use crate::error::Error;
+use crate::models::timeout::to_duration;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// An action server driven with JSON goals, e.g.
+/// `control_msgs/action/GripperCommand`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ActionConfig {
+    /// name of the action in `/actions/:name`
+    pub name: String,
+    pub action_name: String,
+    /// e.g. `control_msgs/action/PointHead`
+    #[serde(rename = "type")]
+    pub action_type: String,
+    #[serde(default)]
+    pub timeouts: ActionTimeouts,
+}
+
+/// Limits applied by the executor watchdog to the goals of an action, in
+/// seconds.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ActionTimeouts {
+    /// how long to wait for the action server before the goal is sent
+    pub availability: f64,
+    /// how long the goal may run without feedback; not checked if omitted
+    pub feedback_silence: Option<f64>,
+    /// how long the goal may run; not checked if omitted
+    pub duration: Option<f64>,
+    /// how often the watchdog checks the limits
+    pub poll_interval: f64,
+}
+
+impl Default for ActionTimeouts {
+    fn default() -> Self {
+        Self {
+            availability: 3.0,
+            feedback_silence: None,
+            duration: None,
+            poll_interval: 0.1,
+        }
+    }
+}
+
+impl ActionTimeouts {
+    /// Returns these timeouts with the fields set in `overrides` replaced.
+    pub fn with_overrides(&self, overrides: &ActionTimeoutOverrides) -> Self {
+        Self {
+            availability: overrides.availability.unwrap_or(self.availability),
+            feedback_silence: overrides.feedback_silence.or(self.feedback_silence),
+            duration: overrides.duration.or(self.duration),
+            poll_interval: self.poll_interval,
+        }
+    }
+
+    pub fn availability(&self) -> Result<Duration, Error> {
+        to_duration("availability", self.availability)
+    }
+
+    pub fn feedback_silence(&self) -> Result<Option<Duration>, Error> {
+        self.feedback_silence
+            .map(|value| to_duration("feedback_silence", value))
+            .transpose()
+    }
+
+    pub fn duration(&self) -> Result<Option<Duration>, Error> {
+        self.duration
+            .map(|value| to_duration("duration", value))
+            .transpose()
+    }
+
+    pub fn poll_interval(&self) -> Result<Duration, Error> {
+        let poll_interval = to_duration("poll_interval", self.poll_interval)?;
+        if poll_interval.is_zero() {
+            return Err(Error::InvalidTimeout {
+                name: "poll_interval",
+                value: self.poll_interval,
+            });
+        }
+        Ok(poll_interval)
+    }
+}
+
+/// Per-request replacement of the action timeouts, in seconds.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct ActionTimeoutOverrides {
+    pub availability: Option<f64>,
+    pub feedback_silence: Option<f64>,
+    pub duration: Option<f64>,
+}
+
+/// Request of `POST /actions/:name/goals`.
+#[derive(Debug, Deserialize)]
+pub struct SendActionGoal {
+    /// fields of the goal, the defaults if omitted
+    #[serde(default = "empty_goal")]
+    pub goal: Value,
+    #[serde(default)]
+    pub timeouts: ActionTimeoutOverrides,
+}
+
+fn empty_goal() -> Value {
+    Value::Object(Default::default())
+}
+
+/// Returned by `GET /actions`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ActionStatus {
+    pub name: String,
+    pub action_name: String,
+    #[serde(rename = "type")]
+    pub action_type: String,
+    pub timeouts: ActionTimeouts,
+    /// goals accepted by the action server and not finished yet
+    pub active_goals: Vec<Uuid>,
+}
@@ -0,0 +1,113 @@
+use crate::models::execution::ExecutionStatus;
+use crate::models::queue::{GoalPolicy, QueuedGoal};
+use crate::models::timeout::TimeoutOverrides;
+use crate::models::trajectory::{CompleteCondition, JointTrajectory};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// A FollowJointTrajectory controller driven by the gateway.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ControllerConfig {
+    pub name: String,
+    pub action_name: String,
+    /// joints of the controller; trajectories are not checked against the
+    /// controller joints if empty
+    #[serde(default)]
+    pub joint_names: Vec<String>,
+}
+
+impl Default for ControllerConfig {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            action_name: "follow_joint_trajectory".to_string(),
+            joint_names: vec![],
+        }
+    }
+}
+
+/// Returned by `GET /controllers/:name/status`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ControllerStatus {
+    pub name: String,
+    pub action_name: String,
+    pub joint_names: Vec<String>,
+    pub policy: GoalPolicy,
+    pub busy: bool,
+    pub current_goal: Option<Uuid>,
+    pub queue: Vec<QueuedGoal>,
+}
+
+/// Query of `/queue`, `/queue/policy`, `/execution_timeouts` and
+/// `/joint_limits`, e.g. `/queue?controller=arm`. The first configured
+/// controller is used if `controller` is omitted.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+pub struct ControllerQuery {
+    #[serde(default)]
+    pub controller: Option<String>,
+}
+
+/// Request of `POST /controllers/:name/execute`.
+#[derive(Debug, Deserialize)]
+pub struct ExecuteTrajectory {
+    pub trajectory: JointTrajectory,
+    #[serde(default)]
+    pub complete_condition: Option<CompleteCondition>,
+    #[serde(default)]
+    pub timeouts: TimeoutOverrides,
+}
+
+/// Request of `POST /controllers/execute`: one trajectory per controller,
+/// all starting `start_delay` seconds after the request.
+#[derive(Debug, Deserialize)]
+pub struct ExecuteControllers {
+    pub goals: BTreeMap<String, ExecuteTrajectory>,
+    #[serde(default = "default_start_delay")]
+    pub start_delay: f64,
+}
+
+fn default_start_delay() -> f64 {
+    0.5
+}
+
+/// Response of `/controllers/execute`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SynchronizedExecution {
+    /// ROS time at which every trajectory starts, in seconds
+    pub start_time: f64,
+    /// goal UUID of each controller
+    pub goals: BTreeMap<String, Uuid>,
+}
+
+/// Request of `POST /trajectories`: a trajectory over the joints of several
+/// controllers, split into one goal per controller.
+#[derive(Debug, Deserialize)]
+pub struct ExecuteMergedTrajectory {
+    pub trajectory: JointTrajectory,
+    /// cancel every piece as soon as one is rejected or does not succeed
+    #[serde(default)]
+    pub atomic: bool,
+    #[serde(default = "default_start_delay")]
+    pub start_delay: f64,
+    #[serde(default)]
+    pub timeouts: TimeoutOverrides,
+}
+
+/// State of a merged trajectory, returned by `GET /trajectories/:id`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct CombinedExecution {
+    pub id: Uuid,
+    pub atomic: bool,
+    /// ROS time at which every piece starts, in seconds
+    pub start_time: f64,
+    /// goal UUID of each controller
+    pub goals: BTreeMap<String, Uuid>,
+    /// reason of each controller which rejected its piece
+    pub rejected: BTreeMap<String, String>,
+    /// `executing` until every piece has finished, then `succeeded` or the
+    /// status of the first piece which did not succeed
+    pub status: ExecutionStatus,
+    pub finished_at: Option<DateTime<Utc>>,
+}
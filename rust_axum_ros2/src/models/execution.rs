@@ -0,0 +1,156 @@
+use crate::models::timeout::TimeoutLimit;
+use crate::models::trajectory::JointTrajectoryPoint;
+use chrono::{DateTime, Utc};
+use r2r::{builtin_interfaces::msg::Time, control_msgs::action::FollowJointTrajectory};
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExecutionStatus {
+    Unknown,
+    Accepted,
+    Executing,
+    Canceling,
+    Succeeded,
+    Canceled,
+    Aborted,
+    /// stopped by the executor watchdog
+    TimedOut,
+}
+
+impl From<r2r::GoalStatus> for ExecutionStatus {
+    fn from(status: r2r::GoalStatus) -> Self {
+        match status {
+            r2r::GoalStatus::Unknown => ExecutionStatus::Unknown,
+            r2r::GoalStatus::Accepted => ExecutionStatus::Accepted,
+            r2r::GoalStatus::Executing => ExecutionStatus::Executing,
+            r2r::GoalStatus::Canceling => ExecutionStatus::Canceling,
+            r2r::GoalStatus::Succeeded => ExecutionStatus::Succeeded,
+            r2r::GoalStatus::Canceled => ExecutionStatus::Canceled,
+            r2r::GoalStatus::Aborted => ExecutionStatus::Aborted,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ExecutionFeedback {
+    pub stamp: Time,
+    pub joint_names: Vec<String>,
+    pub desired: JointTrajectoryPoint,
+    pub actual: JointTrajectoryPoint,
+    pub error: JointTrajectoryPoint,
+}
+
+impl ExecutionFeedback {
+    pub fn from_msg(msg: &FollowJointTrajectory::Feedback) -> ExecutionFeedback {
+        ExecutionFeedback {
+            stamp: msg.header.stamp.clone(),
+            joint_names: msg.joint_names.clone(),
+            desired: JointTrajectoryPoint::from_msg(&msg.desired),
+            actual: JointTrajectoryPoint::from_msg(&msg.actual),
+            error: JointTrajectoryPoint::from_msg(&msg.error),
+        }
+    }
+}
+
+/// Progress of a running goal, pushed to WebSocket clients as JSON.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExecutionEvent {
+    /// `ExecutionFeedback` for controllers, the feedback message for actions
+    Feedback(Value),
+    Status {
+        status: ExecutionStatus,
+    },
+    Result {
+        status: ExecutionStatus,
+        error_code: Option<i32>,
+        error_string: String,
+        result: Option<Value>,
+    },
+}
+
+/// State of a goal, returned by `GET /executions/:uuid`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Execution {
+    pub goal_id: Uuid,
+    /// name of the controller or action running the goal
+    pub controller: String,
+    /// set if the goal waited in the executor queue
+    pub queue_id: Option<Uuid>,
+    pub status: ExecutionStatus,
+    pub accepted_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub last_feedback: Option<Value>,
+    /// `FollowJointTrajectory::Result::error_code`, or the `error_code` field
+    /// of an action result
+    pub error_code: Option<i32>,
+    pub error_string: Option<String>,
+    /// result message of an action
+    pub result: Option<Value>,
+    /// set if the goal was stopped by the executor watchdog
+    pub timeout: Option<TimeoutLimit>,
+}
+
+impl Execution {
+    pub fn new(goal_id: Uuid, controller: String, queue_id: Option<Uuid>) -> Execution {
+        let now = Utc::now();
+        Execution {
+            goal_id,
+            controller,
+            queue_id,
+            status: ExecutionStatus::Accepted,
+            accepted_at: now,
+            updated_at: now,
+            finished_at: None,
+            last_feedback: None,
+            error_code: None,
+            error_string: None,
+            result: None,
+            timeout: None,
+        }
+    }
+}
+
+/// Lifecycle change of any goal in the executor, published on `/events`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum GoalEvent {
+    Queued {
+        queue_id: Uuid,
+    },
+    Dequeued {
+        queue_id: Uuid,
+    },
+    Accepted {
+        goal_id: Uuid,
+        controller: String,
+        queue_id: Option<Uuid>,
+    },
+    Rejected {
+        reason: String,
+    },
+    Executing {
+        goal_id: Uuid,
+    },
+    Canceled {
+        goal_id: Uuid,
+    },
+    TimedOut {
+        goal_id: Uuid,
+        limit: TimeoutLimit,
+    },
+    Finished {
+        goal_id: Uuid,
+        status: ExecutionStatus,
+        error_code: Option<i32>,
+    },
+    /// every piece of a merged trajectory has finished
+    CombinedFinished {
+        id: Uuid,
+        status: ExecutionStatus,
+    },
+}
@@ -0,0 +1,33 @@
+use arci_ros2::JointStateEntry;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Latest state of a joint, returned by `GET /joint_states`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct JointState {
+    pub name: String,
+    pub position: Option<f64>,
+    pub velocity: Option<f64>,
+    pub effort: Option<f64>,
+    /// header stamp of the message, in seconds
+    pub stamp: f64,
+    /// seconds since the message was received
+    pub age: f64,
+    /// true if no message was received for the joint within the staleness limit
+    pub stale: bool,
+}
+
+impl JointState {
+    pub fn from_entry(name: String, entry: &JointStateEntry, stale_after: Duration) -> JointState {
+        let age = entry.received_at.elapsed();
+        JointState {
+            name,
+            position: entry.position,
+            velocity: entry.velocity,
+            effort: entry.effort,
+            stamp: entry.stamp.sec as f64 + entry.stamp.nanosec as f64 * 1e-9,
+            age: age.as_secs_f64(),
+            stale: age > stale_after,
+        }
+    }
+}
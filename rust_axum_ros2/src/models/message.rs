@@ -0,0 +1,90 @@
+use crate::error::Error;
+use r2r::std_msgs;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Version of the JSON published on the `user` and `task` topics. Bumped when
+/// a field is removed or changes meaning.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A record with the schema version, e.g.
+/// `{"schema_version": 1, "id": 3, "username": "alice"}`.
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope<T> {
+    /// messages without a version are read as the current version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schema_version: Option<u32>,
+    #[serde(flatten)]
+    record: T,
+}
+
+/// Encodes `record` as JSON in a `std_msgs/String`.
+pub fn to_json_msg<T: Serialize>(record: &T) -> Result<std_msgs::msg::String, Error> {
+    let envelope = Envelope {
+        schema_version: Some(SCHEMA_VERSION),
+        record,
+    };
+    let data =
+        serde_json::to_string(&envelope).map_err(|e| Error::InvalidMessage(e.to_string()))?;
+    Ok(std_msgs::msg::String { data })
+}
+
+/// Decodes a message encoded by `to_json_msg`.
+pub fn from_json_msg<T: DeserializeOwned>(msg: &std_msgs::msg::String) -> Result<T, Error> {
+    let envelope: Envelope<T> =
+        serde_json::from_str(&msg.data).map_err(|e| Error::InvalidMessage(e.to_string()))?;
+    match envelope.schema_version {
+        Some(version) if version > SCHEMA_VERSION => Err(Error::InvalidMessage(format!(
+            "schema_version {} is newer than {}",
+            version, SCHEMA_VERSION
+        ))),
+        _ => Ok(envelope.record),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task::Task;
+    use crate::models::user::User;
+
+    fn msg(data: &str) -> std_msgs::msg::String {
+        std_msgs::msg::String {
+            data: data.to_string(),
+        }
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let user = User::new(3, "alice".to_string());
+        let msg = user.to_msg().unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&msg.data).unwrap(),
+            serde_json::json!({ "schema_version": SCHEMA_VERSION, "id": 3, "username": "alice" })
+        );
+        assert_eq!(User::from_msg(&msg).unwrap(), user);
+
+        let task = Task::new(7, "pick \"box\"".to_string());
+        assert_eq!(Task::from_msg(&task.to_msg().unwrap()).unwrap(), task);
+    }
+
+    #[test]
+    fn schema_version_is_optional() {
+        let user = User::from_msg(&msg(r#"{"id": 3, "username": "alice"}"#)).unwrap();
+        assert_eq!(user, User::new(3, "alice".to_string()));
+    }
+
+    #[test]
+    fn invalid_messages_are_rejected() {
+        for data in [
+            r#"{"schema_version": 2, "id": 3, "username": "alice"}"#,
+            r#"{"id": 3}"#,
+            "alice",
+        ] {
+            assert!(
+                matches!(User::from_msg(&msg(data)), Err(Error::InvalidMessage(_))),
+                "{}",
+                data
+            );
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::models::queue::QueuedGoal;
+use crate::models::timeout::TimeoutOverrides;
+use crate::models::trajectory::{CompleteCondition, JointTrajectory};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Interpolation {
+    /// constant velocity
+    #[default]
+    Linear,
+    /// zero velocity and acceleration at both ends
+    MinimumJerk,
+}
+
+/// Request of `POST /move_joints`.
+///
+/// The motion takes `duration` seconds if set. Otherwise it is as fast as
+/// the joint velocity limits multiplied by `speed_scale` allow.
+#[derive(Debug, Deserialize)]
+pub struct MoveJoints {
+    /// the default controller if omitted
+    #[serde(default)]
+    pub controller: Option<String>,
+    /// target position of each joint to move
+    pub positions: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub speed_scale: Option<f64>,
+    #[serde(default)]
+    pub interpolation: Interpolation,
+    #[serde(default)]
+    pub complete_condition: Option<CompleteCondition>,
+    #[serde(default)]
+    pub timeouts: TimeoutOverrides,
+}
+
+/// Response of `/move_joints` when the goal was accepted.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct MoveJointsExecution {
+    pub goal_id: Uuid,
+    pub trajectory: JointTrajectory,
+}
+
+/// Response of `/move_joints` when the goal was put in the executor queue.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct MoveJointsQueued {
+    #[serde(flatten)]
+    pub queued: QueuedGoal,
+    pub trajectory: JointTrajectory,
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// Query of the list endpoints, e.g. `/users?offset=20&limit=10`.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct Pagination {
+    #[serde(default)]
+    pub offset: u64,
+    #[serde(default = "default_limit")]
+    pub limit: u64,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            limit: default_limit(),
+        }
+    }
+}
+
+fn default_limit() -> u64 {
+    50
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// number of records in the whole list
+    pub total: u64,
+    pub offset: u64,
+    pub limit: u64,
+}
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// What the executor does with a goal sent while another goal is running.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum GoalPolicy {
+    /// reply 409 to the new goal
+    #[default]
+    RejectIfBusy,
+    /// cancel the running goal, then send the new one
+    Preempt,
+    /// run the goals one after another
+    Queue,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GoalPolicyBody {
+    pub policy: GoalPolicy,
+}
+
+/// A trajectory waiting in the executor queue.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct QueuedGoal {
+    pub queue_id: Uuid,
+    pub enqueued_at: DateTime<Utc>,
+    pub joint_names: Vec<String>,
+    pub num_points: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReorderQueue {
+    pub order: Vec<Uuid>,
+}
@@ -0,0 +1,325 @@
+use crate::models::trajectory::{JointLimit, JointLimits};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Where to read the URDF from.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum UrdfSource {
+    /// only the file of the `[robot_model]` config section
+    File { path: String },
+    /// string parameter of the gateway node
+    Parameter {
+        #[serde(default = "default_robot_description")]
+        name: String,
+    },
+    /// `std_msgs/String` topic published with transient local durability,
+    /// e.g. by `robot_state_publisher`
+    Topic {
+        #[serde(default = "default_robot_description_topic")]
+        topic: String,
+        #[serde(default = "default_topic_timeout_sec")]
+        timeout_sec: f64,
+    },
+}
+
+fn default_robot_description() -> String {
+    "robot_description".to_string()
+}
+
+fn default_robot_description_topic() -> String {
+    "/robot_description".to_string()
+}
+
+fn default_topic_timeout_sec() -> f64 {
+    5.0
+}
+
+/// Body of `POST /robot_model`, also the `[robot_model]` section of the
+/// config loaded at startup.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LoadRobotModel {
+    pub source: UrdfSource,
+    /// joints of the controller; every movable joint if omitted
+    #[serde(default)]
+    pub joint_names: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum JointType {
+    Revolute,
+    Continuous,
+    Prismatic,
+    Fixed,
+    Floating,
+    Planar,
+    Spherical,
+}
+
+impl From<&urdf_rs::JointType> for JointType {
+    fn from(joint_type: &urdf_rs::JointType) -> Self {
+        match joint_type {
+            urdf_rs::JointType::Revolute => JointType::Revolute,
+            urdf_rs::JointType::Continuous => JointType::Continuous,
+            urdf_rs::JointType::Prismatic => JointType::Prismatic,
+            urdf_rs::JointType::Fixed => JointType::Fixed,
+            urdf_rs::JointType::Floating => JointType::Floating,
+            urdf_rs::JointType::Planar => JointType::Planar,
+            urdf_rs::JointType::Spherical => JointType::Spherical,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RobotJoint {
+    pub name: String,
+    pub joint_type: JointType,
+    pub parent_link: String,
+    pub child_link: String,
+    /// the joint whose child link is `parent_link`, `None` for the root joints
+    pub parent_joint: Option<String>,
+    /// set for joints that follow another joint
+    pub mimic: Option<String>,
+    /// `None` for joints without degrees of freedom
+    pub limit: Option<JointLimit>,
+}
+
+impl RobotJoint {
+    /// Returns true for the joints that can be commanded by a trajectory.
+    pub fn is_movable(&self) -> bool {
+        matches!(
+            self.joint_type,
+            JointType::Revolute | JointType::Continuous | JointType::Prismatic
+        ) && self.mimic.is_none()
+    }
+}
+
+/// Joints of the robot, read from the URDF.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RobotModel {
+    pub name: String,
+    pub joints: Vec<RobotJoint>,
+}
+
+impl RobotModel {
+    pub fn from_urdf(robot: &urdf_rs::Robot) -> RobotModel {
+        let joint_by_child: HashMap<&str, &str> = robot
+            .joints
+            .iter()
+            .map(|joint| (joint.child.link.as_str(), joint.name.as_str()))
+            .collect();
+
+        let joints = robot
+            .joints
+            .iter()
+            .map(|joint| {
+                let joint_type = JointType::from(&joint.joint_type);
+                let limit = match joint_type {
+                    JointType::Revolute | JointType::Prismatic => Some(JointLimit {
+                        name: joint.name.clone(),
+                        lower: Some(joint.limit.lower),
+                        upper: Some(joint.limit.upper),
+                        velocity: Some(joint.limit.velocity),
+                        effort: Some(joint.limit.effort),
+                    }),
+                    // continuous joints have no position limits
+                    JointType::Continuous => Some(JointLimit {
+                        name: joint.name.clone(),
+                        lower: None,
+                        upper: None,
+                        velocity: Some(joint.limit.velocity),
+                        effort: Some(joint.limit.effort),
+                    }),
+                    _ => None,
+                };
+                RobotJoint {
+                    name: joint.name.clone(),
+                    joint_type,
+                    parent_link: joint.parent.link.clone(),
+                    child_link: joint.child.link.clone(),
+                    parent_joint: joint_by_child
+                        .get(joint.parent.link.as_str())
+                        .map(|name| name.to_string()),
+                    mimic: joint.mimic.as_ref().map(|mimic| mimic.joint.clone()),
+                    limit,
+                }
+            })
+            .collect();
+
+        RobotModel {
+            name: robot.name.clone(),
+            joints,
+        }
+    }
+
+    pub fn movable_joint_names(&self) -> Vec<String> {
+        self.joints
+            .iter()
+            .filter(|joint| joint.is_movable())
+            .map(|joint| joint.name.clone())
+            .collect()
+    }
+
+    /// Returns the limits of `joint_names`, or of every movable joint.
+    pub fn joint_limits(&self, joint_names: Option<&[String]>) -> Result<JointLimits, arci::Error> {
+        let joint_names = match joint_names {
+            Some(joint_names) => joint_names.to_vec(),
+            None => self.movable_joint_names(),
+        };
+        let joints = joint_names
+            .iter()
+            .map(|name| {
+                let joint = self
+                    .joints
+                    .iter()
+                    .find(|joint| joint.name == *name)
+                    .ok_or_else(|| arci::Error::NoJoint(name.clone()))?;
+                Ok(joint.limit.clone().unwrap_or_else(|| JointLimit {
+                    name: name.clone(),
+                    lower: None,
+                    upper: None,
+                    velocity: None,
+                    effort: None,
+                }))
+            })
+            .collect::<Result<_, arci::Error>>()?;
+        Ok(JointLimits { joints })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URDF: &str = r#"<?xml version="1.0"?>
+<robot name="arm">
+  <link name="base_link"/>
+  <link name="shoulder_link"/>
+  <link name="wrist_link"/>
+  <link name="finger_link"/>
+  <link name="mimic_finger_link"/>
+  <joint name="base_joint" type="fixed">
+    <parent link="base_link"/>
+    <child link="shoulder_link"/>
+  </joint>
+  <joint name="shoulder_joint" type="revolute">
+    <parent link="shoulder_link"/>
+    <child link="wrist_link"/>
+    <limit lower="-1.5" upper="1.5" effort="10.0" velocity="2.0"/>
+  </joint>
+  <joint name="wrist_joint" type="continuous">
+    <parent link="wrist_link"/>
+    <child link="finger_link"/>
+    <limit effort="5.0" velocity="3.0"/>
+  </joint>
+  <joint name="mimic_joint" type="revolute">
+    <parent link="finger_link"/>
+    <child link="mimic_finger_link"/>
+    <limit lower="0.0" upper="0.5" effort="1.0" velocity="1.0"/>
+    <mimic joint="wrist_joint"/>
+  </joint>
+</robot>
+"#;
+
+    fn robot_model() -> RobotModel {
+        RobotModel::from_urdf(&urdf_rs::read_from_string(URDF).unwrap())
+    }
+
+    #[test]
+    fn from_urdf_reads_joints() {
+        let model = robot_model();
+        assert_eq!(model.name, "arm");
+        let joints: Vec<_> = model
+            .joints
+            .iter()
+            .map(|joint| {
+                (
+                    joint.name.as_str(),
+                    joint.joint_type,
+                    joint.parent_joint.as_deref(),
+                    joint.mimic.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            joints,
+            vec![
+                ("base_joint", JointType::Fixed, None, None),
+                (
+                    "shoulder_joint",
+                    JointType::Revolute,
+                    Some("base_joint"),
+                    None
+                ),
+                (
+                    "wrist_joint",
+                    JointType::Continuous,
+                    Some("shoulder_joint"),
+                    None
+                ),
+                (
+                    "mimic_joint",
+                    JointType::Revolute,
+                    Some("wrist_joint"),
+                    Some("wrist_joint")
+                ),
+            ]
+        );
+        assert_eq!(model.joints[0].limit, None);
+        assert_eq!(
+            model.movable_joint_names(),
+            vec!["shoulder_joint", "wrist_joint"]
+        );
+    }
+
+    #[test]
+    fn joint_limits_of_movable_joints() {
+        let limits = robot_model().joint_limits(None).unwrap();
+        assert_eq!(
+            limits.joints,
+            vec![
+                JointLimit {
+                    name: "shoulder_joint".to_string(),
+                    lower: Some(-1.5),
+                    upper: Some(1.5),
+                    velocity: Some(2.0),
+                    effort: Some(10.0),
+                },
+                // continuous joints have no position limits
+                JointLimit {
+                    name: "wrist_joint".to_string(),
+                    lower: None,
+                    upper: None,
+                    velocity: Some(3.0),
+                    effort: Some(5.0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn joint_limits_of_given_joints() {
+        let model = robot_model();
+        let names = ["base_joint".to_string(), "mimic_joint".to_string()];
+        let limits = model.joint_limits(Some(&names)).unwrap();
+        assert_eq!(
+            limits.joints[0],
+            JointLimit {
+                name: "base_joint".to_string(),
+                lower: None,
+                upper: None,
+                velocity: None,
+                effort: None,
+            }
+        );
+        assert_eq!(limits.joints[1].upper, Some(0.5));
+
+        let names = ["elbow_joint".to_string()];
+        assert!(matches!(
+            model.joint_limits(Some(&names)),
+            Err(arci::Error::NoJoint(name)) if name == "elbow_joint"
+        ));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::error::Error;
+use crate::models::timeout::to_duration;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+/// Limits of the service calls, in seconds.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServiceTimeouts {
+    /// how long to wait for the service server before the request is sent
+    pub availability: f64,
+    /// how long to wait for the response once the request is sent
+    pub response: f64,
+}
+
+impl Default for ServiceTimeouts {
+    fn default() -> Self {
+        Self {
+            availability: 3.0,
+            response: 10.0,
+        }
+    }
+}
+
+impl ServiceTimeouts {
+    /// Returns these timeouts with the fields set in `overrides` replaced.
+    pub fn with_overrides(&self, overrides: &ServiceTimeoutOverrides) -> Self {
+        Self {
+            availability: overrides.availability.unwrap_or(self.availability),
+            response: overrides.response.unwrap_or(self.response),
+        }
+    }
+
+    pub fn availability(&self) -> Result<Duration, Error> {
+        to_duration("availability", self.availability)
+    }
+
+    pub fn response(&self) -> Result<Duration, Error> {
+        to_duration("response", self.response)
+    }
+}
+
+/// Per-request replacement of the service timeouts, in seconds.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct ServiceTimeoutOverrides {
+    pub availability: Option<f64>,
+    pub response: Option<f64>,
+}
+
+/// Request of `POST /services/:name/call`.
+#[derive(Debug, Deserialize)]
+pub struct CallService {
+    /// e.g. `controller_manager_msgs/srv/ListControllers`
+    #[serde(rename = "type")]
+    pub srv_type: String,
+    /// fields of the request, the defaults if omitted
+    #[serde(default = "empty_request")]
+    pub request: Value,
+    #[serde(default)]
+    pub timeouts: ServiceTimeoutOverrides,
+}
+
+fn empty_request() -> Value {
+    Value::Object(Default::default())
+}
+
+/// Response of `POST /services/:name/call`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ServiceResponse {
+    pub service: String,
+    #[serde(rename = "type")]
+    pub srv_type: String,
+    pub response: Value,
+    /// seconds from the request to the response
+    pub elapsed: f64,
+}
@@ -1,10 +1,44 @@
+use crate::error::Error;
+use crate::models::message;
+use crate::models::queue::QueuedGoal;
+use crate::models::timeout::TimeoutOverrides;
+use crate::models::trajectory::{CompleteCondition, JointTrajectory};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Debug, Deserialize)]
 pub struct CreateTask {
     pub taskname: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ExecuteTask {
+    pub taskname: String,
+    pub trajectory: JointTrajectory,
+    #[serde(default)]
+    pub complete_condition: Option<CompleteCondition>,
+    /// replaces the executor timeouts for this goal
+    #[serde(default)]
+    pub timeouts: TimeoutOverrides,
+}
+
+/// Response of `/execute_task`. The progress of the goal can be queried at
+/// `/executions/:goal_id`.
+#[derive(Debug, Serialize, Clone, Eq, PartialEq)]
+pub struct TaskExecution {
+    pub task: Task,
+    pub goal_id: Uuid,
+}
+
+/// Response of `/execute_task` when the goal was put in the executor queue.
+/// The goal id is announced by a `queued`/`accepted` pair on `/events`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct TaskQueued {
+    pub task: Task,
+    #[serde(flatten)]
+    pub queued: QueuedGoal,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, Hash, PartialEq)]
 pub struct Task {
     id: u64,
@@ -16,9 +50,16 @@ impl Task {
         Task { id, taskname }
     }
 
-    pub fn to_msg(&self) -> r2r::std_msgs::msg::String {
-        r2r::std_msgs::msg::String {
-            data: self.taskname.clone(),
-        }
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Encodes the whole record as JSON, see `message::to_json_msg`.
+    pub fn to_msg(&self) -> Result<r2r::std_msgs::msg::String, Error> {
+        message::to_json_msg(self)
+    }
+
+    pub fn from_msg(msg: &r2r::std_msgs::msg::String) -> Result<Task, Error> {
+        message::from_json_msg(msg)
     }
 }
@@ -0,0 +1,94 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Limits applied by the executor watchdog, in seconds.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExecutionTimeouts {
+    /// how long to wait for the action server before the goal is sent
+    pub availability: f64,
+    /// how long the goal may run without feedback
+    pub feedback_silence: f64,
+    /// added to the `time_from_start` of the last trajectory point
+    pub duration_margin: f64,
+    /// how often the watchdog checks the limits
+    pub poll_interval: f64,
+}
+
+impl Default for ExecutionTimeouts {
+    fn default() -> Self {
+        Self {
+            availability: 3.0,
+            feedback_silence: 10.0,
+            duration_margin: 5.0,
+            poll_interval: 0.1,
+        }
+    }
+}
+
+impl ExecutionTimeouts {
+    /// Returns these timeouts with the fields set in `overrides` replaced.
+    pub fn with_overrides(&self, overrides: &TimeoutOverrides) -> Self {
+        Self {
+            availability: overrides.availability.unwrap_or(self.availability),
+            feedback_silence: overrides.feedback_silence.unwrap_or(self.feedback_silence),
+            duration_margin: overrides.duration_margin.unwrap_or(self.duration_margin),
+            poll_interval: self.poll_interval,
+        }
+    }
+
+    pub fn availability(&self) -> Result<Duration, Error> {
+        to_duration("availability", self.availability)
+    }
+
+    pub fn feedback_silence(&self) -> Result<Duration, Error> {
+        to_duration("feedback_silence", self.feedback_silence)
+    }
+
+    pub fn duration_margin(&self) -> Result<Duration, Error> {
+        to_duration("duration_margin", self.duration_margin)
+    }
+
+    pub fn poll_interval(&self) -> Result<Duration, Error> {
+        let poll_interval = to_duration("poll_interval", self.poll_interval)?;
+        if poll_interval.is_zero() {
+            return Err(Error::InvalidTimeout {
+                name: "poll_interval",
+                value: self.poll_interval,
+            });
+        }
+        Ok(poll_interval)
+    }
+}
+
+pub(crate) fn to_duration(name: &'static str, value: f64) -> Result<Duration, Error> {
+    Duration::try_from_secs_f64(value).map_err(|_| Error::InvalidTimeout { name, value })
+}
+
+/// Per-request replacement of the executor timeouts, in seconds.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct TimeoutOverrides {
+    pub availability: Option<f64>,
+    pub feedback_silence: Option<f64>,
+    pub duration_margin: Option<f64>,
+}
+
+/// The watchdog limit that stopped a goal.
+#[derive(Debug, Serialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeoutLimit {
+    Acceptance,
+    FeedbackSilence,
+    TotalDuration,
+}
+
+impl std::fmt::Display for TimeoutLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeoutLimit::Acceptance => write!(f, "acceptance"),
+            TimeoutLimit::FeedbackSilence => write!(f, "feedback-silence"),
+            TimeoutLimit::TotalDuration => write!(f, "total-duration"),
+        }
+    }
+}
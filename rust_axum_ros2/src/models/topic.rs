@@ -0,0 +1,213 @@
+use crate::error::Error;
+use chrono::{DateTime, Utc};
+use r2r::QosProfile;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+/// Interface packages the gateway is built with, the `IDL_PACKAGE_FILTER` of
+/// `.cargo/config.toml`. Interfaces of other packages cannot be converted
+/// from or to JSON.
+pub const SUPPORTED_PACKAGES: &[&str] = &[
+    "std_msgs",
+    "sensor_msgs",
+    "trajectory_msgs",
+    "geometry_msgs",
+    "control_msgs",
+    "r2r_minimal_node_msgs",
+    "rcl_interfaces",
+    "lifecycle_msgs",
+    "controller_manager_msgs",
+];
+
+/// Returns true if `msg_type` is `<package>/msg/<Name>` of a supported
+/// package.
+pub fn is_supported_type(msg_type: &str) -> bool {
+    is_supported_interface(msg_type, "msg")
+}
+
+/// Returns true if `name` is `<package>/<kind>/<Name>` of a supported
+/// package, `kind` being `msg`, `srv` or `action`.
+pub fn is_supported_interface(name: &str, kind: &str) -> bool {
+    match name.split('/').collect::<Vec<_>>()[..] {
+        [package, interface_kind, type_name] if interface_kind == kind => {
+            SUPPORTED_PACKAGES.contains(&package)
+                && type_name.starts_with(|c: char| c.is_ascii_uppercase())
+                && type_name.chars().all(|c| c.is_ascii_alphanumeric())
+        }
+        _ => false,
+    }
+}
+
+/// Quality of service of a subscription or a publisher.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum QosPreset {
+    /// reliable, volatile, keep last 10
+    #[default]
+    Default,
+    /// best effort, for high rate sensor topics
+    SensorData,
+    /// reliable and transient local, to receive the last message published
+    /// before the subscription, like `/robot_description`
+    TransientLocal,
+}
+
+impl QosPreset {
+    pub fn to_profile(self) -> QosProfile {
+        match self {
+            QosPreset::Default => QosProfile::default(),
+            QosPreset::SensorData => QosProfile::sensor_data(),
+            QosPreset::TransientLocal => QosProfile::default().transient_local(),
+        }
+    }
+}
+
+/// A ROS topic forwarded to HTTP and WebSocket clients.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TopicBridgeConfig {
+    /// name of the bridge in `/topics/:name`
+    pub name: String,
+    pub topic: String,
+    /// e.g. `sensor_msgs/msg/Imu`
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    #[serde(default)]
+    pub qos: QosPreset,
+    /// minimum seconds between two messages sent to WebSocket clients; every
+    /// message is sent if omitted
+    #[serde(default)]
+    pub throttle: Option<f64>,
+}
+
+impl TopicBridgeConfig {
+    pub fn throttle(&self) -> Result<Option<Duration>, Error> {
+        self.throttle
+            .map(|value| {
+                Duration::try_from_secs_f64(value).map_err(|_| Error::InvalidTimeout {
+                    name: "throttle",
+                    value,
+                })
+            })
+            .transpose()
+    }
+}
+
+/// A topic clients may publish to with `POST /topics/:name/publish`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PublishConfig {
+    pub topic: String,
+    /// types clients may publish on the topic; any supported type if empty
+    #[serde(default)]
+    pub types: Vec<String>,
+}
+
+impl PublishConfig {
+    /// Returns true if `name`, the topic of the request path, is this topic.
+    /// The leading `/` is optional in the path.
+    pub fn matches(&self, name: &str) -> bool {
+        self.topic.trim_start_matches('/') == name.trim_start_matches('/')
+    }
+
+    pub fn allows(&self, msg_type: &str) -> bool {
+        self.types.is_empty() || self.types.iter().any(|allowed| allowed == msg_type)
+    }
+}
+
+/// Request of `POST /topics/:name/publish`.
+#[derive(Debug, Deserialize)]
+pub struct PublishMessage {
+    /// e.g. `geometry_msgs/msg/Twist`
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    pub msg: Value,
+    #[serde(default)]
+    pub qos: QosPreset,
+}
+
+/// Response of `POST /topics/:name/publish`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct PublishedMessage {
+    pub topic: String,
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    pub qos: QosPreset,
+    pub published_at: DateTime<Utc>,
+}
+
+/// Message received on a bridged topic, returned by `GET /topics/:name` and
+/// pushed to WebSocket clients as JSON.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct TopicMessage {
+    pub topic: String,
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    pub received_at: DateTime<Utc>,
+    pub msg: Value,
+}
+
+/// Returned by `GET /topics`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct TopicBridgeStatus {
+    pub name: String,
+    pub topic: String,
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    pub qos: QosPreset,
+    pub throttle: Option<f64>,
+    /// number of messages received since startup
+    pub received: u64,
+    pub last_received_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn publish_config(topic: &str, types: &[&str]) -> PublishConfig {
+        PublishConfig {
+            topic: topic.to_string(),
+            types: types.iter().map(|msg_type| msg_type.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn leading_slash_is_optional() {
+        for topic in ["/cmd_vel", "cmd_vel"] {
+            let config = publish_config(topic, &[]);
+            assert!(config.matches("cmd_vel"));
+            assert!(config.matches("/cmd_vel"));
+            assert!(!config.matches("cmd_vel2"));
+            assert!(!config.matches("/robot/cmd_vel"));
+        }
+        let config = publish_config("/robot/cmd_vel", &[]);
+        assert!(config.matches("robot/cmd_vel"));
+        assert!(!config.matches("cmd_vel"));
+    }
+
+    #[test]
+    fn types_are_allowed() {
+        let config = publish_config("/cmd_vel", &["geometry_msgs/msg/Twist"]);
+        assert!(config.allows("geometry_msgs/msg/Twist"));
+        assert!(!config.allows("geometry_msgs/msg/TwistStamped"));
+        assert!(!config.allows("std_msgs/msg/String"));
+
+        // any type if none is given
+        let config = publish_config("/chatter", &[]);
+        assert!(config.allows("std_msgs/msg/String"));
+    }
+
+    #[test]
+    fn supported_interfaces() {
+        assert!(is_supported_type("std_msgs/msg/String"));
+        assert!(!is_supported_type("std_msgs/srv/String"));
+        assert!(!is_supported_type("std_msgs/msg/string"));
+        assert!(!is_supported_type("my_msgs/msg/Custom"));
+        assert!(is_supported_interface(
+            "control_msgs/action/GripperCommand",
+            "action"
+        ));
+    }
+}
@@ -0,0 +1,420 @@
+use r2r::{builtin_interfaces, trajectory_msgs};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Longest `time_from_start` in seconds, the range of
+/// `builtin_interfaces/Duration::sec`.
+pub const MAX_TIME_FROM_START: f64 = i32::MAX as f64;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct JointTrajectoryPoint {
+    pub positions: Vec<f64>,
+    #[serde(default)]
+    pub velocities: Vec<f64>,
+    #[serde(default)]
+    pub accelerations: Vec<f64>,
+    #[serde(default)]
+    pub effort: Vec<f64>,
+    /// seconds from the start of the trajectory
+    pub time_from_start: f64,
+}
+
+impl JointTrajectoryPoint {
+    pub fn from_msg(msg: &trajectory_msgs::msg::JointTrajectoryPoint) -> JointTrajectoryPoint {
+        JointTrajectoryPoint {
+            positions: msg.positions.clone(),
+            velocities: msg.velocities.clone(),
+            accelerations: msg.accelerations.clone(),
+            effort: msg.effort.clone(),
+            time_from_start: msg.time_from_start.sec as f64
+                + msg.time_from_start.nanosec as f64 * 1e-9,
+        }
+    }
+
+    /// Fails if `time_from_start` is negative or does not fit in the message.
+    pub fn to_msg(&self) -> Result<trajectory_msgs::msg::JointTrajectoryPoint, arci::Error> {
+        Ok(trajectory_msgs::msg::JointTrajectoryPoint {
+            positions: self.positions.clone(),
+            velocities: self.velocities.clone(),
+            accelerations: self.accelerations.clone(),
+            effort: self.effort.clone(),
+            time_from_start: duration_msg(self.time_from_start)?,
+        })
+    }
+}
+
+fn duration_msg(seconds: f64) -> Result<builtin_interfaces::msg::Duration, arci::Error> {
+    let out_of_range = || arci::Error::TimeFromStartOutOfRange {
+        time_from_start: seconds,
+        max: MAX_TIME_FROM_START,
+    };
+    let duration = Duration::try_from_secs_f64(seconds).map_err(|_| out_of_range())?;
+    Ok(builtin_interfaces::msg::Duration {
+        sec: i32::try_from(duration.as_secs()).map_err(|_| out_of_range())?,
+        nanosec: duration.subsec_nanos(),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct JointTrajectory {
+    pub joint_names: Vec<String>,
+    pub points: Vec<JointTrajectoryPoint>,
+}
+
+impl JointTrajectory {
+    /// Converts to the ROS message. The header is left empty and is filled
+    /// in by the executor when the goal is sent.
+    pub fn to_msg(&self) -> Result<trajectory_msgs::msg::JointTrajectory, arci::Error> {
+        Ok(trajectory_msgs::msg::JointTrajectory {
+            joint_names: self.joint_names.clone(),
+            points: self
+                .points
+                .iter()
+                .map(|p| p.to_msg())
+                .collect::<Result<_, _>>()?,
+            ..Default::default()
+        })
+    }
+
+    /// Returns the trajectory of the joints at `indices`.
+    pub fn select_joints(&self, indices: &[usize]) -> JointTrajectory {
+        // omitted vectors stay empty
+        let select = |values: &Vec<f64>| -> Vec<f64> {
+            if values.is_empty() {
+                return vec![];
+            }
+            indices.iter().map(|i| values[*i]).collect()
+        };
+        JointTrajectory {
+            joint_names: indices
+                .iter()
+                .map(|i| self.joint_names[*i].clone())
+                .collect(),
+            points: self
+                .points
+                .iter()
+                .map(|point| JointTrajectoryPoint {
+                    positions: select(&point.positions),
+                    velocities: select(&point.velocities),
+                    accelerations: select(&point.accelerations),
+                    effort: select(&point.effort),
+                    time_from_start: point.time_from_start,
+                })
+                .collect(),
+        }
+    }
+
+    /// Checks the trajectory before it is sent to the controller.
+    ///
+    /// The joint names must be the joints of `limits` and every vector of a
+    /// point must have one value per joint. `time_from_start` must strictly
+    /// increase up to `MAX_TIME_FROM_START` and positions and velocities must
+    /// be within the limits.
+    /// Only the shape and the timing are checked if `limits` has no joints.
+    pub fn validate(&self, limits: &JointLimits) -> Result<(), arci::Error> {
+        let num_joints = self.joint_names.len();
+        if !limits.joints.is_empty() {
+            let mut names = self.joint_names.clone();
+            let mut controller_names = limits.joint_names();
+            names.sort();
+            names.dedup();
+            controller_names.sort();
+            if names.len() != num_joints || names != controller_names {
+                return Err(arci::Error::JointNamesMismatch {
+                    partial: self.joint_names.clone(),
+                    full: limits.joint_names(),
+                });
+            }
+        }
+
+        let mut previous = None;
+        for (index, point) in self.points.iter().enumerate() {
+            // velocities, accelerations and effort may be omitted
+            let vectors = [
+                (&point.positions, false),
+                (&point.velocities, true),
+                (&point.accelerations, true),
+                (&point.effort, true),
+            ];
+            for (values, optional) in vectors {
+                if values.len() != num_joints && !(optional && values.is_empty()) {
+                    return Err(arci::Error::LengthMismatch {
+                        model: num_joints,
+                        input: values.len(),
+                    });
+                }
+            }
+
+            let is_increasing = match previous {
+                Some(previous) => point.time_from_start > previous,
+                None => point.time_from_start >= 0.0,
+            };
+            if !is_increasing {
+                return Err(arci::Error::InvalidTimeFromStart {
+                    index,
+                    time_from_start: point.time_from_start,
+                    previous: previous.unwrap_or(0.0),
+                });
+            }
+            duration_msg(point.time_from_start)?;
+            previous = Some(point.time_from_start);
+
+            for (i, name) in self.joint_names.iter().enumerate() {
+                let Some(limit) = limits.get(name) else {
+                    continue;
+                };
+                limit.check_position(point.positions[i])?;
+                if let Some(velocity) = point.velocities.get(i) {
+                    limit.check_velocity(*velocity)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Limits of a controller joint. Unset limits are not checked.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct JointLimit {
+    pub name: String,
+    #[serde(default)]
+    pub lower: Option<f64>,
+    #[serde(default)]
+    pub upper: Option<f64>,
+    /// maximum absolute velocity
+    #[serde(default)]
+    pub velocity: Option<f64>,
+    /// maximum absolute effort, informational only
+    #[serde(default)]
+    pub effort: Option<f64>,
+}
+
+impl JointLimit {
+    pub fn check_position(&self, position: f64) -> Result<(), arci::Error> {
+        let lower = self.lower.unwrap_or(f64::NEG_INFINITY);
+        let upper = self.upper.unwrap_or(f64::INFINITY);
+        if !(lower..=upper).contains(&position) {
+            return Err(arci::Error::OutOfLimit {
+                name: self.name.clone(),
+                position,
+                limit: lower..=upper,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn check_velocity(&self, velocity: f64) -> Result<(), arci::Error> {
+        let limit = self.velocity.unwrap_or(f64::INFINITY);
+        if velocity.is_nan() || velocity.abs() > limit {
+            return Err(arci::Error::VelocityOutOfLimit {
+                name: self.name.clone(),
+                velocity,
+                limit,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Joints of a controller, used to validate trajectories before they are sent.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct JointLimits {
+    pub joints: Vec<JointLimit>,
+}
+
+impl JointLimits {
+    pub fn joint_names(&self) -> Vec<String> {
+        self.joints.iter().map(|joint| joint.name.clone()).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&JointLimit> {
+        self.joints.iter().find(|joint| joint.name == name)
+    }
+}
+
+/// Condition to decide that a trajectory execution is complete, in addition to
+/// the result reported by the action server.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CompleteCondition {
+    TotalJointDiff {
+        allowable_error: f64,
+        timeout_sec: f64,
+    },
+    EachJointDiff {
+        allowable_errors: Vec<f64>,
+        timeout_sec: f64,
+    },
+}
+
+impl CompleteCondition {
+    pub fn to_arci(&self) -> Box<dyn arci::CompleteCondition> {
+        match self {
+            CompleteCondition::TotalJointDiff {
+                allowable_error,
+                timeout_sec,
+            } => Box::new(arci::TotalJointDiffCondition::new(
+                *allowable_error,
+                *timeout_sec,
+            )),
+            CompleteCondition::EachJointDiff {
+                allowable_errors,
+                timeout_sec,
+            } => Box::new(arci::EachJointDiffCondition::new(
+                allowable_errors.clone(),
+                *timeout_sec,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(name: &str) -> JointLimit {
+        JointLimit {
+            name: name.to_string(),
+            lower: Some(-1.0),
+            upper: Some(1.0),
+            velocity: Some(2.0),
+            effort: None,
+        }
+    }
+
+    fn limits() -> JointLimits {
+        JointLimits {
+            joints: vec![limit("a"), limit("b")],
+        }
+    }
+
+    fn point(positions: Vec<f64>, time_from_start: f64) -> JointTrajectoryPoint {
+        JointTrajectoryPoint {
+            positions,
+            velocities: vec![],
+            accelerations: vec![],
+            effort: vec![],
+            time_from_start,
+        }
+    }
+
+    fn trajectory(points: Vec<JointTrajectoryPoint>) -> JointTrajectory {
+        JointTrajectory {
+            joint_names: vec!["b".to_string(), "a".to_string()],
+            points,
+        }
+    }
+
+    #[test]
+    fn validate_accepts_joints_in_any_order() {
+        let trajectory = trajectory(vec![
+            point(vec![0.0, 0.0], 0.0),
+            point(vec![0.5, -1.0], 1.0),
+        ]);
+        trajectory.validate(&limits()).unwrap();
+        trajectory.validate(&JointLimits::default()).unwrap();
+    }
+
+    #[test]
+    fn validate_rejects_other_joints() {
+        let mut trajectory = trajectory(vec![point(vec![0.0, 0.0], 1.0)]);
+        trajectory.joint_names = vec!["a".to_string(), "a".to_string()];
+        assert!(matches!(
+            trajectory.validate(&limits()),
+            Err(arci::Error::JointNamesMismatch { .. })
+        ));
+        trajectory.joint_names = vec!["a".to_string(), "c".to_string()];
+        assert!(matches!(
+            trajectory.validate(&limits()),
+            Err(arci::Error::JointNamesMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_wrong_lengths() {
+        let trajectory = trajectory(vec![point(vec![0.0], 1.0)]);
+        assert!(matches!(
+            trajectory.validate(&JointLimits::default()),
+            Err(arci::Error::LengthMismatch { model: 2, input: 1 })
+        ));
+
+        let mut point = point(vec![0.0, 0.0], 1.0);
+        point.velocities = vec![0.0];
+        assert!(matches!(
+            self::trajectory(vec![point]).validate(&JointLimits::default()),
+            Err(arci::Error::LengthMismatch { model: 2, input: 1 })
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_invalid_time_from_start() {
+        for times in [vec![-0.1], vec![1.0, 1.0], vec![1.0, 0.5], vec![f64::NAN]] {
+            let points = times
+                .into_iter()
+                .map(|t| point(vec![0.0, 0.0], t))
+                .collect();
+            assert!(matches!(
+                trajectory(points).validate(&limits()),
+                Err(arci::Error::InvalidTimeFromStart { .. })
+            ));
+        }
+        for time_from_start in [1e30, f64::INFINITY, MAX_TIME_FROM_START + 1.0] {
+            let trajectory = trajectory(vec![point(vec![0.0, 0.0], time_from_start)]);
+            assert!(matches!(
+                trajectory.validate(&limits()),
+                Err(arci::Error::TimeFromStartOutOfRange { .. })
+            ));
+            assert!(trajectory.to_msg().is_err());
+        }
+    }
+
+    #[test]
+    fn validate_checks_limits() {
+        let trajectory = trajectory(vec![point(vec![0.0, 1.5], 1.0)]);
+        assert!(matches!(
+            trajectory.validate(&limits()),
+            Err(arci::Error::OutOfLimit { .. })
+        ));
+        // unchecked without limits
+        trajectory.validate(&JointLimits::default()).unwrap();
+
+        let mut point = point(vec![0.0, 0.0], 1.0);
+        point.velocities = vec![0.0, -3.0];
+        assert!(matches!(
+            self::trajectory(vec![point]).validate(&limits()),
+            Err(arci::Error::VelocityOutOfLimit { .. })
+        ));
+    }
+
+    #[test]
+    fn check_position() {
+        let limit = limit("a");
+        limit.check_position(-1.0).unwrap();
+        limit.check_position(1.0).unwrap();
+        assert!(limit.check_position(1.01).is_err());
+        assert!(limit.check_position(f64::NAN).is_err());
+
+        let unlimited = JointLimit {
+            lower: None,
+            upper: None,
+            ..limit
+        };
+        unlimited.check_position(1e10).unwrap();
+    }
+
+    #[test]
+    fn check_velocity() {
+        let limit = limit("a");
+        limit.check_velocity(-2.0).unwrap();
+        limit.check_velocity(2.0).unwrap();
+        assert!(limit.check_velocity(-2.01).is_err());
+        assert!(limit.check_velocity(f64::NAN).is_err());
+
+        let unlimited = JointLimit {
+            velocity: None,
+            ..limit
+        };
+        unlimited.check_velocity(1e10).unwrap();
+        assert!(unlimited.check_velocity(f64::NAN).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::error::Error;
+use crate::models::message;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
@@ -16,9 +18,16 @@ impl User {
         User { id, username }
     }
 
-    pub fn to_msg(&self) -> r2r::std_msgs::msg::String {
-        r2r::std_msgs::msg::String {
-            data: self.username.clone(),
-        }
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Encodes the whole record as JSON, see `message::to_json_msg`.
+    pub fn to_msg(&self) -> Result<r2r::std_msgs::msg::String, Error> {
+        message::to_json_msg(self)
+    }
+
+    pub fn from_msg(msg: &r2r::std_msgs::msg::String) -> Result<User, Error> {
+        message::from_json_msg(msg)
     }
 }
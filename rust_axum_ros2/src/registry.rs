@@ -0,0 +1,403 @@
+use crate::models::controller::CombinedExecution;
+use crate::models::execution::{Execution, ExecutionEvent, ExecutionStatus, GoalEvent};
+use crate::models::timeout::TimeoutLimit;
+use chrono::Utc;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+const EXECUTION_EVENT_CAPACITY: usize = 64;
+const GOAL_EVENT_CAPACITY: usize = 64;
+// number of finished executions kept for `GET /executions/:uuid`
+const FINISHED_EXECUTION_HISTORY: usize = 256;
+
+struct Entry {
+    execution: Execution,
+    // dropped when the execution finishes, which closes the subscribers' streams
+    events: Option<broadcast::Sender<ExecutionEvent>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<Uuid, Entry>,
+    finished: VecDeque<Uuid>,
+    combined: HashMap<Uuid, CombinedExecution>,
+    finished_combined: VecDeque<Uuid>,
+}
+
+/// Keeps the state of every goal sent by an executor and fans out their
+/// events to WebSocket and SSE subscribers.
+#[derive(Clone)]
+pub struct ExecutionRegistry {
+    inner: Arc<Mutex<Inner>>,
+    goal_events: broadcast::Sender<GoalEvent>,
+}
+
+impl Default for ExecutionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExecutionRegistry {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner::default())),
+            goal_events: broadcast::channel(GOAL_EVENT_CAPACITY).0,
+        }
+    }
+
+    pub fn get(&self, goal_id: &Uuid) -> Option<Execution> {
+        self.inner
+            .lock()
+            .unwrap()
+            .entries
+            .get(goal_id)
+            .map(|entry| entry.execution.clone())
+    }
+
+    /// Subscribes to the events of a running goal. Returns `None` if the goal
+    /// is unknown or already finished.
+    pub fn subscribe(&self, goal_id: &Uuid) -> Option<broadcast::Receiver<ExecutionEvent>> {
+        self.inner
+            .lock()
+            .unwrap()
+            .entries
+            .get(goal_id)
+            .and_then(|entry| entry.events.as_ref())
+            .map(|events| events.subscribe())
+    }
+
+    /// Subscribes to the lifecycle events of all goals.
+    pub fn subscribe_goal_events(&self) -> broadcast::Receiver<GoalEvent> {
+        self.goal_events.subscribe()
+    }
+
+    // Send errors are ignored below, they only mean nobody is listening.
+
+    pub fn queued(&self, queue_id: Uuid) {
+        let _ = self.goal_events.send(GoalEvent::Queued { queue_id });
+    }
+
+    pub fn dequeued(&self, queue_id: Uuid) {
+        let _ = self.goal_events.send(GoalEvent::Dequeued { queue_id });
+    }
+
+    pub fn accepted(&self, goal_id: Uuid, controller: &str, queue_id: Option<Uuid>) {
+        let (events, _) = broadcast::channel(EXECUTION_EVENT_CAPACITY);
+        let _ = events.send(ExecutionEvent::Status {
+            status: ExecutionStatus::Accepted,
+        });
+        let entry = Entry {
+            execution: Execution::new(goal_id, controller.to_string(), queue_id),
+            events: Some(events),
+        };
+        self.inner.lock().unwrap().entries.insert(goal_id, entry);
+        let _ = self.goal_events.send(GoalEvent::Accepted {
+            goal_id,
+            controller: controller.to_string(),
+            queue_id,
+        });
+    }
+
+    pub fn rejected(&self, reason: String) {
+        let _ = self.goal_events.send(GoalEvent::Rejected { reason });
+    }
+
+    pub fn feedback(&self, goal_id: &Uuid, feedback: Value) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(entry) = inner.entries.get_mut(goal_id) {
+            entry.execution.updated_at = Utc::now();
+            entry.execution.last_feedback = Some(feedback.clone());
+            if let Some(events) = &entry.events {
+                let _ = events.send(ExecutionEvent::Feedback(feedback));
+            }
+        }
+    }
+
+    pub fn set_status(&self, goal_id: &Uuid, status: ExecutionStatus) {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(entry) = inner.entries.get_mut(goal_id) else {
+            return;
+        };
+        if entry.execution.status == status || entry.execution.finished_at.is_some() {
+            return;
+        }
+        entry.execution.status = status;
+        entry.execution.updated_at = Utc::now();
+        if let Some(events) = &entry.events {
+            let _ = events.send(ExecutionEvent::Status { status });
+        }
+        if status == ExecutionStatus::Executing {
+            let _ = self
+                .goal_events
+                .send(GoalEvent::Executing { goal_id: *goal_id });
+        }
+    }
+
+    pub fn canceled(&self, goal_id: &Uuid) {
+        self.set_status(goal_id, ExecutionStatus::Canceling);
+        let _ = self
+            .goal_events
+            .send(GoalEvent::Canceled { goal_id: *goal_id });
+    }
+
+    /// Ignored if the goal has already finished, e.g. when its result arrived
+    /// while the watchdog was canceling it.
+    pub fn timed_out(&self, goal_id: &Uuid, limit: TimeoutLimit, error_string: String) {
+        match self.inner.lock().unwrap().entries.get_mut(goal_id) {
+            Some(entry) if entry.execution.finished_at.is_none() => {
+                entry.execution.timeout = Some(limit);
+            }
+            _ => return,
+        }
+        let _ = self.goal_events.send(GoalEvent::TimedOut {
+            goal_id: *goal_id,
+            limit,
+        });
+        self.finish(
+            goal_id,
+            ExecutionStatus::TimedOut,
+            None,
+            Some(error_string),
+            None,
+        );
+    }
+
+    pub fn finished(
+        &self,
+        goal_id: &Uuid,
+        status: ExecutionStatus,
+        error_code: Option<i32>,
+        error_string: String,
+        result: Option<Value>,
+    ) {
+        if self.finish(goal_id, status, error_code, Some(error_string), result) {
+            let _ = self.goal_events.send(GoalEvent::Finished {
+                goal_id: *goal_id,
+                status,
+                error_code,
+            });
+        }
+    }
+
+    pub fn get_combined(&self, id: &Uuid) -> Option<CombinedExecution> {
+        self.inner.lock().unwrap().combined.get(id).cloned()
+    }
+
+    pub fn combined_started(&self, combined: CombinedExecution) {
+        self.inner
+            .lock()
+            .unwrap()
+            .combined
+            .insert(combined.id, combined);
+    }
+
+    pub fn combined_finished(&self, id: &Uuid, status: ExecutionStatus) {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(combined) = inner.combined.get_mut(id) else {
+            return;
+        };
+        combined.status = status;
+        combined.finished_at = Some(Utc::now());
+
+        inner.finished_combined.push_back(*id);
+        while inner.finished_combined.len() > FINISHED_EXECUTION_HISTORY {
+            if let Some(oldest) = inner.finished_combined.pop_front() {
+                inner.combined.remove(&oldest);
+            }
+        }
+        drop(inner);
+        let _ = self
+            .goal_events
+            .send(GoalEvent::CombinedFinished { id: *id, status });
+    }
+
+    /// Waits until the goal has finished and returns its final status.
+    pub async fn wait_finished(&self, goal_id: &Uuid) -> ExecutionStatus {
+        if let Some(mut events) = self.subscribe(goal_id) {
+            loop {
+                match events.recv().await {
+                    Ok(ExecutionEvent::Result { status, .. }) => return status,
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+        // already finished, or the result event was missed
+        self.get(goal_id)
+            .map(|execution| execution.status)
+            .unwrap_or(ExecutionStatus::Unknown)
+    }
+
+    /// Returns false if the goal is unknown or has already finished.
+    fn finish(
+        &self,
+        goal_id: &Uuid,
+        status: ExecutionStatus,
+        error_code: Option<i32>,
+        error_string: Option<String>,
+        result: Option<Value>,
+    ) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(entry) = inner.entries.get_mut(goal_id) else {
+            return false;
+        };
+        if entry.execution.finished_at.is_some() {
+            return false;
+        }
+
+        let now = Utc::now();
+        entry.execution.status = status;
+        entry.execution.updated_at = now;
+        entry.execution.finished_at = Some(now);
+        entry.execution.error_code = error_code;
+        entry.execution.error_string = error_string.clone();
+        entry.execution.result = result.clone();
+        if let Some(events) = entry.events.take() {
+            let _ = events.send(ExecutionEvent::Result {
+                status,
+                error_code,
+                error_string: error_string.unwrap_or_default(),
+                result,
+            });
+        }
+
+        inner.finished.push_back(*goal_id);
+        while inner.finished.len() > FINISHED_EXECUTION_HISTORY {
+            if let Some(oldest) = inner.finished.pop_front() {
+                inner.entries.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finish(registry: &ExecutionRegistry, goal_id: &Uuid, status: ExecutionStatus) {
+        registry.finished(goal_id, status, Some(0), String::new(), None);
+    }
+
+    #[test]
+    fn execution_goes_through_its_states() {
+        let registry = ExecutionRegistry::new();
+        let mut goal_events = registry.subscribe_goal_events();
+        let goal_id = Uuid::new_v4();
+
+        registry.accepted(goal_id, "arm", None);
+        let mut events = registry.subscribe(&goal_id).unwrap();
+        assert_eq!(
+            registry.get(&goal_id).unwrap().status,
+            ExecutionStatus::Accepted
+        );
+
+        registry.set_status(&goal_id, ExecutionStatus::Executing);
+        assert_eq!(
+            registry.get(&goal_id).unwrap().status,
+            ExecutionStatus::Executing
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            ExecutionEvent::Status {
+                status: ExecutionStatus::Executing
+            }
+        );
+
+        finish(&registry, &goal_id, ExecutionStatus::Succeeded);
+        let execution = registry.get(&goal_id).unwrap();
+        assert_eq!(execution.status, ExecutionStatus::Succeeded);
+        assert!(execution.finished_at.is_some());
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            ExecutionEvent::Result {
+                status: ExecutionStatus::Succeeded,
+                ..
+            }
+        ));
+        // the stream of a finished goal is closed
+        assert_eq!(
+            events.try_recv(),
+            Err(broadcast::error::TryRecvError::Closed)
+        );
+        assert!(registry.subscribe(&goal_id).is_none());
+
+        assert!(matches!(
+            goal_events.try_recv().unwrap(),
+            GoalEvent::Accepted { .. }
+        ));
+        assert!(matches!(
+            goal_events.try_recv().unwrap(),
+            GoalEvent::Executing { .. }
+        ));
+        assert!(matches!(
+            goal_events.try_recv().unwrap(),
+            GoalEvent::Finished {
+                status: ExecutionStatus::Succeeded,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn finished_goal_is_not_finished_again() {
+        let registry = ExecutionRegistry::new();
+        let goal_id = Uuid::new_v4();
+        registry.accepted(goal_id, "arm", None);
+        let mut goal_events = registry.subscribe_goal_events();
+
+        registry.timed_out(
+            &goal_id,
+            TimeoutLimit::FeedbackSilence,
+            "no feedback".to_string(),
+        );
+        // e.g. the result of the canceled goal
+        finish(&registry, &goal_id, ExecutionStatus::Canceled);
+        registry.set_status(&goal_id, ExecutionStatus::Executing);
+
+        let execution = registry.get(&goal_id).unwrap();
+        assert_eq!(execution.status, ExecutionStatus::TimedOut);
+        assert_eq!(execution.timeout, Some(TimeoutLimit::FeedbackSilence));
+        assert_eq!(execution.error_string.as_deref(), Some("no feedback"));
+        assert!(matches!(
+            goal_events.try_recv().unwrap(),
+            GoalEvent::TimedOut { .. }
+        ));
+        assert_eq!(
+            goal_events.try_recv(),
+            Err(broadcast::error::TryRecvError::Empty)
+        );
+
+        // unknown goals are ignored too
+        finish(&registry, &Uuid::new_v4(), ExecutionStatus::Succeeded);
+        assert_eq!(
+            goal_events.try_recv(),
+            Err(broadcast::error::TryRecvError::Empty)
+        );
+    }
+
+    #[test]
+    fn oldest_finished_executions_are_evicted() {
+        let registry = ExecutionRegistry::new();
+        let running = Uuid::new_v4();
+        registry.accepted(running, "arm", None);
+        let goal_ids: Vec<_> = (0..=FINISHED_EXECUTION_HISTORY)
+            .map(|_| Uuid::new_v4())
+            .collect();
+        for goal_id in &goal_ids {
+            registry.accepted(*goal_id, "arm", None);
+            finish(&registry, goal_id, ExecutionStatus::Succeeded);
+        }
+
+        assert!(registry.get(&goal_ids[0]).is_none());
+        assert!(registry.get(&goal_ids[1]).is_some());
+        assert!(registry.get(goal_ids.last().unwrap()).is_some());
+        assert!(registry.get(&running).is_some());
+    }
+}
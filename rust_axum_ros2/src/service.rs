@@ -0,0 +1,86 @@
+use crate::error::Error;
+use crate::models::service::{CallService, ServiceResponse, ServiceTimeouts};
+use crate::models::topic;
+use arci_ros2::Node;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+use tokio::time::timeout;
+
+/// Untyped clients of the services called over HTTP, created on the first
+/// call of each service and type. A client lives as long as the node, so
+/// the clients are reused instead of being created per call.
+pub struct ServiceClients {
+    node: Node,
+    timeouts: ServiceTimeouts,
+    clients: Mutex<HashMap<(String, String), Arc<r2r::ClientUntyped>>>,
+}
+
+impl ServiceClients {
+    pub fn new(node: Node, timeouts: ServiceTimeouts) -> ServiceClients {
+        ServiceClients {
+            node,
+            timeouts,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Calls the service `name`, waiting for the server the same way goals
+    /// wait for the action server. `name` is absolute, the leading `/` being
+    /// optional. Resolves to the response as JSON.
+    pub fn call(
+        &self,
+        name: &str,
+        request: CallService,
+    ) -> Result<tokio::task::JoinHandle<Result<ServiceResponse, Error>>, Error> {
+        if !topic::is_supported_interface(&request.srv_type, "srv") {
+            return Err(Error::InvalidMessage(format!(
+                "type {} is not supported",
+                request.srv_type
+            )));
+        }
+        let timeouts = self.timeouts.with_overrides(&request.timeouts);
+        let availability = timeouts.availability()?;
+        let response_timeout = timeouts.response()?;
+
+        let service = format!("/{}", name.trim_start_matches('/'));
+        let client = self.client(&service, &request.srv_type)?;
+        let is_available = self.node.r2r().is_available(&*client)?;
+
+        Ok(tokio::spawn(async move {
+            match timeout(availability, is_available).await {
+                Ok(res) => res?,
+                Err(_) => return Err(Error::ServiceUnavailable(service, availability)),
+            }
+
+            let start = Instant::now();
+            let response = client
+                .request(request.request)
+                .map_err(Error::from_untyped)?;
+            let response = match timeout(response_timeout, response).await {
+                Ok(response) => response?.map_err(Error::from_untyped)?,
+                Err(_) => return Err(Error::ServiceTimeout(service, response_timeout)),
+            };
+            Ok(ServiceResponse {
+                service,
+                srv_type: request.srv_type,
+                response,
+                elapsed: start.elapsed().as_secs_f64(),
+            })
+        }))
+    }
+
+    fn client(&self, service: &str, srv_type: &str) -> Result<Arc<r2r::ClientUntyped>, Error> {
+        let mut clients = self.clients.lock().unwrap();
+        let key = (service.to_string(), srv_type.to_string());
+        if let Some(client) = clients.get(&key) {
+            return Ok(client.clone());
+        }
+        log::info!("creating client of {} ({})", service, srv_type);
+        let client = Arc::new(self.node.r2r().create_client_untyped(service, srv_type)?);
+        clients.insert(key, client.clone());
+        Ok(client)
+    }
+}
@@ -0,0 +1,415 @@
+use crate::config::StorageConfig;
+use crate::error::Error;
+use crate::models::page::{Page, Pagination};
+use crate::models::task::Task;
+use crate::models::user::User;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    sync::Mutex,
+};
+
+// largest page returned by the list endpoints
+const MAX_PAGE_LIMIT: u64 = 1000;
+
+/// A named record of the catalogue, stored with a generated id.
+pub trait Record: Sized {
+    /// table of the record
+    const TABLE: &'static str;
+    /// name of the record in error messages
+    const KIND: &'static str;
+
+    fn from_row(id: u64, name: String) -> Self;
+}
+
+impl Record for User {
+    const TABLE: &'static str = "users";
+    const KIND: &'static str = "User";
+
+    fn from_row(id: u64, name: String) -> Self {
+        User::new(id, name)
+    }
+}
+
+impl Record for Task {
+    const TABLE: &'static str = "tasks";
+    const KIND: &'static str = "Task";
+
+    fn from_row(id: u64, name: String) -> Self {
+        Task::new(id, name)
+    }
+}
+
+/// Storage of `(id, name)` rows per table. Ids are never reused, even after
+/// the row was deleted.
+pub trait StoreBackend: Send + Sync {
+    fn insert(&self, table: &'static str, name: &str) -> Result<u64, Error>;
+    fn get(&self, table: &'static str, id: u64) -> Result<Option<String>, Error>;
+    /// Returns the smallest id of the rows named `name`.
+    fn find(&self, table: &'static str, name: &str) -> Result<Option<u64>, Error>;
+    /// Returns the rows of the page, ordered by id, and the number of rows.
+    fn list(
+        &self,
+        table: &'static str,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<(u64, String)>, u64), Error>;
+    /// Returns false if no row has `id`.
+    fn update(&self, table: &'static str, id: u64, name: &str) -> Result<bool, Error>;
+    /// Returns the name of the deleted row.
+    fn delete(&self, table: &'static str, id: u64) -> Result<Option<String>, Error>;
+}
+
+/// Users and tasks created through the REST API.
+pub struct Store {
+    backend: Box<dyn StoreBackend>,
+}
+
+impl Store {
+    pub fn open(config: &StorageConfig) -> Result<Store, Error> {
+        let backend: Box<dyn StoreBackend> = match config {
+            StorageConfig::Memory => Box::<MemoryBackend>::default(),
+            StorageConfig::Sqlite { path } => Box::new(SqliteBackend::open(path)?),
+        };
+        Ok(Store { backend })
+    }
+
+    pub fn create<R: Record>(&self, name: &str) -> Result<R, Error> {
+        check_name::<R>(name)?;
+        let id = self.backend.insert(R::TABLE, name)?;
+        Ok(R::from_row(id, name.to_string()))
+    }
+
+    pub fn get<R: Record>(&self, id: u64) -> Result<R, Error> {
+        match self.backend.get(R::TABLE, id)? {
+            Some(name) => Ok(R::from_row(id, name)),
+            None => Err(not_found::<R>(id)),
+        }
+    }
+
+    /// Returns the oldest record named `name`, creating it if there is none.
+    pub fn find_or_create<R: Record>(&self, name: &str) -> Result<R, Error> {
+        check_name::<R>(name)?;
+        match self.backend.find(R::TABLE, name)? {
+            Some(id) => Ok(R::from_row(id, name.to_string())),
+            None => self.create(name),
+        }
+    }
+
+    pub fn list<R: Record>(&self, pagination: &Pagination) -> Result<Page<R>, Error> {
+        let limit = pagination.limit.min(MAX_PAGE_LIMIT);
+        let (rows, total) = self.backend.list(R::TABLE, pagination.offset, limit)?;
+        Ok(Page {
+            items: rows
+                .into_iter()
+                .map(|(id, name)| R::from_row(id, name))
+                .collect(),
+            total,
+            offset: pagination.offset,
+            limit,
+        })
+    }
+
+    pub fn update<R: Record>(&self, id: u64, name: &str) -> Result<R, Error> {
+        check_name::<R>(name)?;
+        if !self.backend.update(R::TABLE, id, name)? {
+            return Err(not_found::<R>(id));
+        }
+        Ok(R::from_row(id, name.to_string()))
+    }
+
+    pub fn delete<R: Record>(&self, id: u64) -> Result<R, Error> {
+        match self.backend.delete(R::TABLE, id)? {
+            Some(name) => Ok(R::from_row(id, name)),
+            None => Err(not_found::<R>(id)),
+        }
+    }
+}
+
+fn check_name<R: Record>(name: &str) -> Result<(), Error> {
+    if name.trim().is_empty() {
+        return Err(Error::InvalidRecord(format!(
+            "{} name must not be empty",
+            R::KIND
+        )));
+    }
+    Ok(())
+}
+
+fn not_found<R: Record>(id: u64) -> Error {
+    Error::RecordNotFound { kind: R::KIND, id }
+}
+
+#[derive(Default)]
+struct Table {
+    last_id: u64,
+    rows: BTreeMap<u64, String>,
+}
+
+/// Keeps the records until the process exits.
+#[derive(Default)]
+pub struct MemoryBackend {
+    tables: Mutex<HashMap<&'static str, Table>>,
+}
+
+impl StoreBackend for MemoryBackend {
+    fn insert(&self, table: &'static str, name: &str) -> Result<u64, Error> {
+        let mut tables = self.tables.lock().unwrap();
+        let table = tables.entry(table).or_default();
+        table.last_id += 1;
+        table.rows.insert(table.last_id, name.to_string());
+        Ok(table.last_id)
+    }
+
+    fn get(&self, table: &'static str, id: u64) -> Result<Option<String>, Error> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables
+            .get(table)
+            .and_then(|table| table.rows.get(&id).cloned()))
+    }
+
+    fn find(&self, table: &'static str, name: &str) -> Result<Option<u64>, Error> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables.get(table).and_then(|table| {
+            table
+                .rows
+                .iter()
+                .find(|(_, row)| row.as_str() == name)
+                .map(|(id, _)| *id)
+        }))
+    }
+
+    fn list(
+        &self,
+        table: &'static str,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<(u64, String)>, u64), Error> {
+        let tables = self.tables.lock().unwrap();
+        let Some(table) = tables.get(table) else {
+            return Ok((vec![], 0));
+        };
+        let rows = table
+            .rows
+            .iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|(id, name)| (*id, name.clone()))
+            .collect();
+        Ok((rows, table.rows.len() as u64))
+    }
+
+    fn update(&self, table: &'static str, id: u64, name: &str) -> Result<bool, Error> {
+        let mut tables = self.tables.lock().unwrap();
+        match tables
+            .get_mut(table)
+            .and_then(|table| table.rows.get_mut(&id))
+        {
+            Some(row) => {
+                *row = name.to_string();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn delete(&self, table: &'static str, id: u64) -> Result<Option<String>, Error> {
+        let mut tables = self.tables.lock().unwrap();
+        Ok(tables
+            .get_mut(table)
+            .and_then(|table| table.rows.remove(&id)))
+    }
+}
+
+/// Keeps the records in a SQLite database, so they survive restarts.
+pub struct SqliteBackend {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteBackend {
+    /// Opens the database at `path`, creating it and its tables if needed.
+    pub fn open(path: &Path) -> Result<SqliteBackend, Error> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent).map_err(anyhow::Error::from)?;
+        }
+        let connection = Connection::open(path)?;
+        for table in [User::TABLE, Task::TABLE] {
+            // AUTOINCREMENT keeps the ids of deleted rows from being reused
+            connection.execute(
+                &format!(
+                    "CREATE TABLE IF NOT EXISTS {} (
+                        id INTEGER PRIMARY KEY AUTOINCREMENT,
+                        name TEXT NOT NULL
+                    )",
+                    table
+                ),
+                [],
+            )?;
+        }
+        log::info!("opened store {}", path.display());
+        Ok(SqliteBackend {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+impl StoreBackend for SqliteBackend {
+    fn insert(&self, table: &'static str, name: &str) -> Result<u64, Error> {
+        let connection = self.connection.lock().unwrap();
+        connection.execute(
+            &format!("INSERT INTO {} (name) VALUES (?1)", table),
+            params![name],
+        )?;
+        Ok(connection.last_insert_rowid() as u64)
+    }
+
+    fn get(&self, table: &'static str, id: u64) -> Result<Option<String>, Error> {
+        let connection = self.connection.lock().unwrap();
+        let name = connection
+            .query_row(
+                &format!("SELECT name FROM {} WHERE id = ?1", table),
+                params![id as i64],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(name)
+    }
+
+    fn find(&self, table: &'static str, name: &str) -> Result<Option<u64>, Error> {
+        let connection = self.connection.lock().unwrap();
+        let id: Option<i64> = connection.query_row(
+            &format!("SELECT MIN(id) FROM {} WHERE name = ?1", table),
+            params![name],
+            |row| row.get(0),
+        )?;
+        Ok(id.map(|id| id as u64))
+    }
+
+    fn list(
+        &self,
+        table: &'static str,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<(u64, String)>, u64), Error> {
+        let connection = self.connection.lock().unwrap();
+        let total: i64 =
+            connection.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })?;
+        let mut statement = connection.prepare(&format!(
+            "SELECT id, name FROM {} ORDER BY id LIMIT ?1 OFFSET ?2",
+            table
+        ))?;
+        let rows = statement
+            .query_map(params![limit as i64, offset as i64], |row| {
+                Ok((row.get::<_, i64>(0)? as u64, row.get(1)?))
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok((rows, total as u64))
+    }
+
+    fn update(&self, table: &'static str, id: u64, name: &str) -> Result<bool, Error> {
+        let connection = self.connection.lock().unwrap();
+        let updated = connection.execute(
+            &format!("UPDATE {} SET name = ?1 WHERE id = ?2", table),
+            params![name, id as i64],
+        )?;
+        Ok(updated > 0)
+    }
+
+    fn delete(&self, table: &'static str, id: u64) -> Result<Option<String>, Error> {
+        let connection = self.connection.lock().unwrap();
+        let name = connection
+            .query_row(
+                &format!("DELETE FROM {} WHERE id = ?1 RETURNING name", table),
+                params![id as i64],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(offset: u64, limit: u64) -> Pagination {
+        Pagination { offset, limit }
+    }
+
+    fn check_store(store: &Store) {
+        let first = store.create::<Task>("pick").unwrap();
+        let second = store.create::<Task>("place").unwrap();
+        assert_eq!(first, Task::new(1, "pick".to_string()));
+        assert_eq!(second, Task::new(2, "place".to_string()));
+        assert_eq!(store.get::<Task>(2).unwrap(), second);
+        assert!(matches!(
+            store.create::<Task>(" "),
+            Err(Error::InvalidRecord(_))
+        ));
+
+        // tables do not share ids
+        assert_eq!(store.create::<User>("alice").unwrap().id(), 1);
+
+        assert_eq!(store.find_or_create::<Task>("pick").unwrap(), first);
+        let third = store.find_or_create::<Task>("wave").unwrap();
+        assert_eq!(third.id(), 3);
+
+        let listed = store.list::<Task>(&page(1, 1)).unwrap();
+        assert_eq!(listed.items, vec![second.clone()]);
+        assert_eq!(listed.total, 3);
+        let clamped = store.list::<Task>(&page(0, MAX_PAGE_LIMIT + 1)).unwrap();
+        assert_eq!(clamped.limit, MAX_PAGE_LIMIT);
+        assert_eq!(clamped.items.len(), 3);
+
+        let updated = store.update::<Task>(2, "drop").unwrap();
+        assert_eq!(store.get::<Task>(2).unwrap(), updated);
+        assert!(matches!(
+            store.update::<Task>(9, "drop"),
+            Err(Error::RecordNotFound { id: 9, .. })
+        ));
+
+        assert_eq!(store.delete::<Task>(1).unwrap(), first);
+        assert!(matches!(
+            store.get::<Task>(1),
+            Err(Error::RecordNotFound { id: 1, .. })
+        ));
+        assert!(matches!(
+            store.delete::<Task>(1),
+            Err(Error::RecordNotFound { id: 1, .. })
+        ));
+        // ids of deleted records are not reused
+        assert_eq!(store.find_or_create::<Task>("pick").unwrap().id(), 4);
+    }
+
+    #[test]
+    fn memory_store() {
+        check_store(&Store::open(&StorageConfig::Memory).unwrap());
+    }
+
+    #[test]
+    fn sqlite_store() {
+        let path = std::env::temp_dir().join(format!(
+            "rust_axum_ros2_store_{}.sqlite",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let config = StorageConfig::Sqlite { path: path.clone() };
+        check_store(&Store::open(&config).unwrap());
+
+        // the records survive a restart
+        let store = Store::open(&config).unwrap();
+        assert_eq!(
+            store.get::<Task>(4).unwrap(),
+            Task::new(4, "pick".to_string())
+        );
+        assert_eq!(store.create::<Task>("wave").unwrap().id(), 5);
+        drop(store);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,215 +1,590 @@
+use crate::action::{
+    Action, ActionExecutor, GoalAcceptance, GoalOutcome, SentGoal, WatchdogLimits,
+};
 use crate::error::Error;
-use arci_ros2::Node;
-use futures::stream::StreamExt;
+use crate::models::controller::{ControllerConfig, ControllerStatus};
+use crate::models::execution::ExecutionFeedback;
+use crate::models::queue::{GoalPolicy, QueuedGoal};
+use crate::models::timeout::{ExecutionTimeouts, TimeoutOverrides};
+use crate::models::trajectory::{JointLimit, JointLimits};
+use crate::registry::ExecutionRegistry;
+use arci::CompleteCondition;
+use arci_ros2::{JointStateCache, Node, Ros2JointTrajectoryClient};
+use chrono::{DateTime, Utc};
+use futures::{future::BoxFuture, FutureExt, StreamExt};
 use r2r::{
     builtin_interfaces::msg::Time, control_msgs::action::FollowJointTrajectory,
-    std_msgs::msg::Header, trajectory_msgs::msg::JointTrajectory,
+    trajectory_msgs::msg::JointTrajectory,
 };
+use serde_json::Value;
 use std::{
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
-    },
+    collections::VecDeque,
+    sync::{Arc, Mutex},
     time::Duration,
 };
-use tokio::sync::broadcast;
-use tokio::time::timeout;
+use tokio::sync::oneshot;
+use uuid::Uuid;
 
 type FollowJointTrajectoryAction = FollowJointTrajectory::Action;
-type FollowJointTrajectoryActionClient = r2r::ActionClient<FollowJointTrajectoryAction>;
-type FollowJointTrajectoryActionGoal = r2r::ActionClientGoal<FollowJointTrajectoryAction>;
-type FollowJointTrajectoryActionGoalOption = Option<FollowJointTrajectoryActionGoal>;
 
+pub enum Submission {
+    Sent(GoalAcceptance),
+    Queued(QueuedGoal),
+}
+
+/// Goal whose timeouts are resolved, sent with `submit_prepared`.
+pub struct PreparedGoal {
+    goal: PendingGoal,
+    acceptance: GoalAcceptance,
+}
+
+struct PendingGoal {
+    queue_id: Uuid,
+    enqueued_at: DateTime<Utc>,
+    trajectory: JointTrajectory,
+    complete_condition: Option<Box<dyn CompleteCondition>>,
+    limits: WatchdogLimits,
+    acceptance_tx: oneshot::Sender<Result<Uuid, Error>>,
+}
+
+impl PendingGoal {
+    fn to_queued(&self) -> QueuedGoal {
+        QueuedGoal {
+            queue_id: self.queue_id,
+            enqueued_at: self.enqueued_at,
+            joint_names: self.trajectory.joint_names.clone(),
+            num_points: self.trajectory.points.len(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct GoalQueue {
+    policy: GoalPolicy,
+    // true while a goal is being sent or executed
+    busy: bool,
+    pending: VecDeque<PendingGoal>,
+}
+
+impl GoalQueue {
+    fn queued(&self) -> Vec<QueuedGoal> {
+        self.pending.iter().map(|goal| goal.to_queued()).collect()
+    }
+
+    /// Reorders the pending goals. `order` must contain every queued goal
+    /// exactly once.
+    fn reorder(&mut self, order: &[Uuid]) -> Result<(), Error> {
+        let current: Vec<Uuid> = self.pending.iter().map(|goal| goal.queue_id).collect();
+        let is_permutation = order.len() == current.len()
+            && current.iter().all(|id| order.contains(id))
+            && order.iter().all(|id| current.contains(id));
+        if !is_permutation {
+            return Err(Error::QueueMismatch {
+                queued: current,
+                requested: order.to_vec(),
+            });
+        }
+
+        let mut pending: Vec<_> = self.pending.drain(..).map(Some).collect();
+        for id in order {
+            let index = current.iter().position(|queued| queued == id);
+            if let Some(goal) = index.and_then(|index| pending[index].take()) {
+                self.pending.push_back(goal);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sends the goals of a controller through its `Ros2JointTrajectoryClient`,
+/// which also provides the joint positions checked by complete conditions.
+#[derive(Clone)]
 pub struct FollowJointTrajectoryActionExecutor {
-    action_client: FollowJointTrajectoryActionClient,
-    node: Node,
-    current_goal: Arc<Mutex<FollowJointTrajectoryActionGoalOption>>,
+    executor: ActionExecutor<Ros2JointTrajectoryClient>,
+    client: Ros2JointTrajectoryClient,
+    action_name: String,
+    queue: Arc<Mutex<GoalQueue>>,
+    timeouts: Arc<Mutex<ExecutionTimeouts>>,
+    joint_limits: Arc<Mutex<JointLimits>>,
 }
 
 impl FollowJointTrajectoryActionExecutor {
-    #[track_caller]
-    pub fn new(node: Node, action_name: &str) -> Self {
-        let action_client = node
-            .r2r()
-            .create_action_client::<FollowJointTrajectoryAction>(action_name)
-            .unwrap();
-        Self {
-            action_client,
-            node,
-            current_goal: Arc::new(Mutex::new(None)),
+    pub fn new(
+        node: Node,
+        config: &ControllerConfig,
+        policy: GoalPolicy,
+        timeouts: ExecutionTimeouts,
+        joint_states: JointStateCache,
+        executions: ExecutionRegistry,
+    ) -> Result<Self, Error> {
+        let client = Ros2JointTrajectoryClient::with_joint_state_cache(
+            node.clone(),
+            &config.action_name,
+            config.joint_names.clone(),
+            joint_states,
+        )?;
+        // only the joint names are checked until limits are set
+        let joint_limits = JointLimits {
+            joints: config
+                .joint_names
+                .iter()
+                .map(|name| JointLimit {
+                    name: name.clone(),
+                    lower: None,
+                    upper: None,
+                    velocity: None,
+                    effort: None,
+                })
+                .collect(),
+        };
+        Ok(Self {
+            executor: ActionExecutor::new(
+                node,
+                &config.name,
+                &config.action_name,
+                client.clone(),
+                executions,
+            ),
+            client,
+            action_name: config.action_name.clone(),
+            queue: Arc::new(Mutex::new(GoalQueue {
+                policy,
+                ..Default::default()
+            })),
+            timeouts: Arc::new(Mutex::new(timeouts)),
+            joint_limits: Arc::new(Mutex::new(joint_limits)),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        self.executor.name()
+    }
+
+    pub fn executions(&self) -> &ExecutionRegistry {
+        self.executor.executions()
+    }
+
+    /// Returns true while a goal is being sent or executed.
+    pub fn is_busy(&self) -> bool {
+        self.queue.lock().unwrap().busy
+    }
+
+    pub fn status(&self) -> ControllerStatus {
+        let queue = self.queue.lock().unwrap();
+        ControllerStatus {
+            name: self.name().to_string(),
+            action_name: self.action_name.clone(),
+            joint_names: self.joint_limits.lock().unwrap().joint_names(),
+            policy: queue.policy,
+            busy: queue.busy,
+            current_goal: self.executor.current_goal(),
+            queue: queue.queued(),
+        }
+    }
+
+    /// Sends the goal, or applies the goal policy if another goal is running.
+    pub fn submit(
+        &self,
+        trajectory: JointTrajectory,
+        complete_condition: Option<Box<dyn CompleteCondition>>,
+        overrides: &TimeoutOverrides,
+    ) -> Result<Submission, Error> {
+        let goal = self.prepare(trajectory, complete_condition, overrides)?;
+        self.submit_prepared(goal)
+    }
+
+    /// Resolves the timeouts of a goal without sending it, so that the goals
+    /// of several controllers are all checked before any of them is sent.
+    pub fn prepare(
+        &self,
+        trajectory: JointTrajectory,
+        complete_condition: Option<Box<dyn CompleteCondition>>,
+        overrides: &TimeoutOverrides,
+    ) -> Result<PreparedGoal, Error> {
+        let timeouts = self.timeouts.lock().unwrap().with_overrides(overrides);
+        let limits = WatchdogLimits::try_from(&timeouts)?;
+        let (acceptance_tx, acceptance) = oneshot::channel();
+        let goal = PendingGoal {
+            queue_id: Uuid::new_v4(),
+            enqueued_at: Utc::now(),
+            trajectory,
+            complete_condition,
+            limits,
+            acceptance_tx,
+        };
+        Ok(PreparedGoal { goal, acceptance })
+    }
+
+    /// Sends a prepared goal, or applies the goal policy if another goal is
+    /// running.
+    pub fn submit_prepared(&self, prepared: PreparedGoal) -> Result<Submission, Error> {
+        let PreparedGoal { goal, acceptance } = prepared;
+        let mut queue = self.queue.lock().unwrap();
+        if !queue.busy {
+            queue.busy = true;
+            drop(queue);
+            self.send_goal(goal, None);
+            return Ok(Submission::Sent(acceptance));
+        }
+
+        match queue.policy {
+            GoalPolicy::RejectIfBusy => Err(Error::Busy),
+            GoalPolicy::Queue => {
+                let queued = goal.to_queued();
+                log::info!("goal queued: {}", queued.queue_id);
+                queue.pending.push_back(goal);
+                self.executions().queued(queued.queue_id);
+                Ok(Submission::Queued(queued))
+            }
+            GoalPolicy::Preempt => {
+                // the new goal replaces the queued ones and is sent as soon as
+                // the running one has finished
+                let replaced: Vec<_> = queue.pending.drain(..).collect();
+                queue.pending.push_back(goal);
+                drop(queue);
+                for goal in replaced {
+                    self.dequeue(goal);
+                }
+                match self.cancel_goal() {
+                    Ok(_) | Err(Error::NoValidGoalExists) => {}
+                    Err(e) => log::error!("failed to preempt goal: {:?}", e),
+                }
+                Ok(Submission::Sent(acceptance))
+            }
         }
     }
 
-    pub fn send_goal(&self) -> r2r::Result<tokio::task::JoinHandle<()>> {
-        let node = self.node.clone();
-        let current_goal = self.current_goal.clone();
-        let action_client = self.action_client.clone();
-        let is_available = node.r2r().is_available(&action_client)?;
+    fn send_goal(&self, goal: PendingGoal, queue_id: Option<Uuid>) {
+        let executor = self.clone();
+        tokio::spawn(async move {
+            let PendingGoal {
+                trajectory,
+                complete_condition,
+                limits,
+                acceptance_tx,
+                ..
+            } = goal;
+            executor
+                .run_goal(
+                    trajectory,
+                    complete_condition,
+                    limits,
+                    queue_id,
+                    acceptance_tx,
+                )
+                .await;
+            executor.send_next_goal();
+        });
+    }
 
-        let action_handler = tokio::spawn(async move {
-            // wait for action server to be available
-            log::info!("waiting for action server...");
-            if let Err(e) = timeout(Duration::from_millis(3000), is_available).await {
-                log::error!("action server is not available: {:?}", e);
-                return;
+    /// Sends the first goal of the queue, or marks the executor as idle.
+    fn send_next_goal(&self) {
+        let mut queue = self.queue.lock().unwrap();
+        match queue.pending.pop_front() {
+            Some(goal) => {
+                drop(queue);
+                log::info!("goal dequeued: {}", goal.queue_id);
+                let queue_id = goal.queue_id;
+                self.send_goal(goal, Some(queue_id));
             }
-            log::info!("action server is available");
-
-            let now = std::time::Instant::now();
-            let last_update_time = Arc::new(Mutex::new(now));
-            let last_update_time_clone = last_update_time.clone();
-
-            let is_done = Arc::new(AtomicBool::new(false));
-            let is_done_clone = is_done.clone();
-            let current_goal_clone = current_goal.clone();
-
-            let (cancel_tx, mut cancel_rx1) = broadcast::channel(1);
-            let mut cancel_rx2 = cancel_tx.subscribe();
-
-            // spawn a task to handle goal request
-            tokio::spawn(async move {
-                let mut clock = r2r::Clock::create(r2r::ClockType::RosTime)
-                    .expect("failed to create RosTime clock");
-                let now = clock
-                    .get_now()
-                    .expect("failed to get now from RosTime clock");
-
-                let goal = FollowJointTrajectory::Goal {
-                    trajectory: JointTrajectory {
-                        header: Header {
-                            frame_id: "".to_string(),
-                            stamp: Time {
-                                sec: now.as_secs() as i32,
-                                nanosec: now.subsec_nanos(),
-                            },
-                        },
-                        joint_names: vec!["joint1".to_string(), "joint2".to_string()],
-                        points: vec![],
-                    },
-                    ..Default::default()
+            None => queue.busy = false,
+        }
+    }
+
+    async fn run_goal(
+        &self,
+        trajectory: JointTrajectory,
+        complete_condition: Option<Box<dyn CompleteCondition>>,
+        limits: WatchdogLimits,
+        queue_id: Option<Uuid>,
+        acceptance_tx: oneshot::Sender<Result<Uuid, Error>>,
+    ) {
+        // joint positions are checked against the last point of the trajectory
+        let completion = match complete_condition {
+            Some(condition) => {
+                let client = self.client.with_joint_names(trajectory.joint_names.clone());
+                let target_positions = trajectory
+                    .points
+                    .last()
+                    .map(|p| p.positions.clone())
+                    .unwrap_or_default();
+                let completion = async move {
+                    condition
+                        .wait(&client, &target_positions, 0.0)
+                        .await
+                        .map_err(|e| e.to_string())
                 };
+                Some(completion.boxed())
+            }
+            None => None,
+        };
 
-                let send_goal_request = action_client
-                    .send_goal_request(goal)
-                    .expect("failed to send goal request");
-
-                let (goal, result, feedback) = send_goal_request
-                    .await
-                    .expect("goal rejected by action server");
-
-                // update current_goal
-                current_goal_clone.lock().unwrap().replace(goal.clone());
-
-                log::info!("goal_accepted: {}", goal.uuid);
-
-                // spawn a task to handle feedback
-                tokio::spawn(async move {
-                    let goal = goal.clone();
-
-                    // wait for feedback
-                    tokio::select! {
-                        _ = feedback
-                            .for_each(|msg| {
-                                // update last_update_time_nsec
-                                let now = std::time::Instant::now();
-                                *last_update_time.lock().unwrap() = now;
-
-                                log::debug!(
-                                    "feedback: {:?} -- {:?}",
-                                    msg.header.stamp,
-                                    goal.get_status()
-                                );
-                                std::future::ready(())
-                            }) => {
-                                log::info!("feedback finished");
-                            }
-                        v = cancel_rx1.recv() => {
-                            match v {
-                                Ok(_) => {
-                                    log::info!("feedback cancel_rx.recv() finished");
-                                }
-                                Err(broadcast::error::RecvError::Closed) => {
-                                    log::debug!("feedback cancel_rx.recv() closed");
-                                }
-                                Err(e) => {
-                                    log::error!("feedback cancel_rx.recv() error: {:?}", e);
-                                }
-                            }
-                        }
-                    }
-                });
-
-                // wait for result
-                tokio::select! {
-                    r = result => {
-                        match r {
-                            Ok((status, msg)) => {
-                                log::info!("Got result {} with msg {:?}", status, msg);
-                            }
-                            Err(e) => {
-                                log::error!("Action failed: {:?}", e);
-                            }
-                        }
-                        is_done.store(true, Ordering::Relaxed);
-                    }
-                    v = cancel_rx2.recv() => {
-                        match v {
-                            Ok(_) => {
-                                log::info!("wait result cancel_rx.recv() finished");
-                            }
-                            Err(broadcast::error::RecvError::Closed) => {
-                                log::debug!("wait result cancel_rx.recv() closed");
-                            }
-                            Err(e) => {
-                                log::error!("wait result cancel_rx.recv() error: {:?}", e);
-                            }
-                        }
-                    }
-                }
-            });
+        let goal = FollowJointTrajectory::Goal {
+            trajectory,
+            ..Default::default()
+        };
+        self.executor
+            .run_goal(goal, completion, limits, queue_id, acceptance_tx)
+            .await;
+    }
 
-            // check if action is completed or timed out
-            let timeout = Duration::new(10, 0);
-            loop {
-                tokio::time::sleep(Duration::from_millis(100)).await;
+    pub fn goal_policy(&self) -> GoalPolicy {
+        self.queue.lock().unwrap().policy
+    }
 
-                // check if action is completed
-                if is_done_clone.load(Ordering::Relaxed) {
-                    log::info!("action completed");
-                    break;
-                }
+    pub fn set_goal_policy(&self, policy: GoalPolicy) {
+        log::info!("goal policy: {:?}", policy);
+        self.queue.lock().unwrap().policy = policy;
+    }
+
+    pub fn timeouts(&self) -> ExecutionTimeouts {
+        *self.timeouts.lock().unwrap()
+    }
+
+    /// Sets the timeouts of the goals submitted from now on.
+    pub fn set_timeouts(&self, timeouts: ExecutionTimeouts) -> Result<(), Error> {
+        WatchdogLimits::try_from(&timeouts)?;
+        log::info!("execution timeouts: {:?}", timeouts);
+        *self.timeouts.lock().unwrap() = timeouts;
+        Ok(())
+    }
+
+    pub fn joint_limits(&self) -> JointLimits {
+        self.joint_limits.lock().unwrap().clone()
+    }
+
+    pub fn set_joint_limits(&self, joint_limits: JointLimits) {
+        log::info!("joint limits: {:?}", joint_limits);
+        *self.joint_limits.lock().unwrap() = joint_limits;
+    }
 
-                // check if action is timed out
-                let last_update_time = *last_update_time_clone.lock().unwrap();
-                let now = std::time::Instant::now();
-                let elapsed_from_last_update = now - last_update_time;
+    pub fn queued_goals(&self) -> Vec<QueuedGoal> {
+        self.queue.lock().unwrap().queued()
+    }
 
-                if elapsed_from_last_update >= timeout {
-                    log::warn!("action timed out");
-                    cancel_tx.send("cancel").unwrap();
-                    break;
+    /// Reorders the queue. `order` must contain every queued goal exactly once.
+    pub fn reorder_queue(&self, order: &[Uuid]) -> Result<Vec<QueuedGoal>, Error> {
+        let mut queue = self.queue.lock().unwrap();
+        queue.reorder(order)?;
+        Ok(queue.queued())
+    }
+
+    pub fn remove_queued_goal(&self, queue_id: &Uuid) -> Result<QueuedGoal, Error> {
+        let mut queue = self.queue.lock().unwrap();
+        let goal = queue
+            .pending
+            .iter()
+            .position(|goal| goal.queue_id == *queue_id)
+            .and_then(|index| queue.pending.remove(index))
+            .ok_or(Error::GoalNotFound(*queue_id))?;
+        drop(queue);
+        Ok(self.dequeue(goal))
+    }
+
+    pub fn clear_queue(&self) -> Vec<QueuedGoal> {
+        let pending: Vec<_> = self.queue.lock().unwrap().pending.drain(..).collect();
+        pending.into_iter().map(|goal| self.dequeue(goal)).collect()
+    }
+
+    fn dequeue(&self, goal: PendingGoal) -> QueuedGoal {
+        let queued = goal.to_queued();
+        log::info!("goal removed from queue: {}", queued.queue_id);
+        let _ = goal
+            .acceptance_tx
+            .send(Err(Error::Dequeued(queued.queue_id)));
+        self.executions().dequeued(queued.queue_id);
+        queued
+    }
+
+    /// Empties the queue and cancels every goal accepted by the action server.
+    pub fn cancel_all(&self) -> Vec<tokio::task::JoinHandle<Result<(), Error>>> {
+        self.clear_queue();
+        self.executor.cancel_all()
+    }
+
+    pub fn cancel_goal(&self) -> Result<tokio::task::JoinHandle<Result<(), Error>>, Error> {
+        self.executor.cancel_goal()
+    }
+
+    pub fn cancel_goal_by_id(
+        &self,
+        goal_id: &Uuid,
+    ) -> Result<tokio::task::JoinHandle<Result<(), Error>>, Error> {
+        self.executor.cancel_goal_by_id(goal_id)
+    }
+
+    /// Asks the action server to cancel the goal `goal_id`, which may have
+    /// been sent by another process.
+    pub fn cancel_goal_on_server(
+        &self,
+        goal_id: Uuid,
+    ) -> Result<tokio::task::JoinHandle<Result<(), Error>>, Error> {
+        let availability = self.timeouts().availability()?;
+        self.executor.cancel_goal_on_server(goal_id, availability)
+    }
+}
+
+impl Action for Ros2JointTrajectoryClient {
+    type Goal = FollowJointTrajectory::Goal;
+    type Feedback = FollowJointTrajectory::Feedback;
+    type Result = FollowJointTrajectory::Result;
+    type GoalHandle = r2r::ActionClientGoal<FollowJointTrajectoryAction>;
+
+    fn is_available(&self, node: &Node) -> r2r::Result<BoxFuture<'static, r2r::Result<()>>> {
+        Ok(node.r2r().is_available(self.action_client())?.boxed())
+    }
+
+    fn send_goal_request(
+        &self,
+        goal: Self::Goal,
+    ) -> r2r::Result<BoxFuture<'static, r2r::Result<SentGoal<Self>>>> {
+        let request = self.action_client().send_goal_request(goal)?;
+        Ok(async move {
+            let (goal, result, feedback) = request.await?;
+            Ok((goal, result.boxed(), feedback.boxed()))
+        }
+        .boxed())
+    }
+
+    fn prepare_goal(goal: &mut Self::Goal) -> Result<Option<Duration>, Error> {
+        let trajectory = &mut goal.trajectory;
+        // keep the start time chosen by the caller
+        if trajectory.header.stamp == Time::default() {
+            let now =
+                r2r::Clock::create(r2r::ClockType::RosTime).and_then(|mut clock| clock.get_now());
+            match now {
+                Ok(now) => {
+                    trajectory.header.stamp = Time {
+                        sec: now.as_secs() as i32,
+                        nanosec: now.subsec_nanos(),
+                    };
+                }
+                Err(e) => {
+                    log::error!("failed to get now from RosTime clock: {:?}", e);
+                    return Err(e.into());
                 }
             }
+        }
 
-            // clear current_goal
-            current_goal.lock().unwrap().take();
-        });
+        // the goal may run until its last point
+        let expected_duration = trajectory
+            .points
+            .last()
+            .map(|p| {
+                Duration::new(
+                    p.time_from_start.sec.max(0) as u64,
+                    p.time_from_start.nanosec,
+                )
+            })
+            .unwrap_or_default();
+        // a goal stamped in the future, e.g. a synchronized one, starts later
+        Ok(Some(
+            start_delay(&trajectory.header.stamp) + expected_duration,
+        ))
+    }
 
-        Ok(action_handler)
+    fn feedback_to_json(feedback: Self::Feedback) -> Option<Value> {
+        serde_json::to_value(ExecutionFeedback::from_msg(&feedback)).ok()
     }
 
-    pub fn cancel_goal(&self) -> Result<tokio::task::JoinHandle<()>, Error> {
-        if let Some(current_goal) = self.current_goal.lock().unwrap().take() {
-            log::warn!("cancel goal: {:?}", current_goal.uuid);
-            let fut = current_goal.cancel().map_err(|e| Error::Other(e.into()))?;
-            let cancel_handler = tokio::spawn(async move {
-                let _ = fut.await;
-                log::warn!("canceled goal: {:?}", current_goal.uuid);
-            });
+    fn outcome(result: Self::Result) -> GoalOutcome {
+        GoalOutcome {
+            error_code: Some(result.error_code),
+            error_string: result.error_string,
+            result: None,
+        }
+    }
+}
+
+/// Returns how long until `stamp`, zero for an unset or past stamp.
+fn start_delay(stamp: &Time) -> Duration {
+    if *stamp == Time::default() {
+        return Duration::ZERO;
+    }
+    let now = r2r::Clock::create(r2r::ClockType::RosTime).and_then(|mut clock| clock.get_now());
+    match now {
+        Ok(now) => Duration::new(stamp.sec.max(0) as u64, stamp.nanosec).saturating_sub(now),
+        Err(e) => {
+            log::error!("failed to get now from RosTime clock: {:?}", e);
+            Duration::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending_goal() -> (PendingGoal, GoalAcceptance) {
+        let (acceptance_tx, acceptance) = oneshot::channel();
+        let goal = PendingGoal {
+            queue_id: Uuid::new_v4(),
+            enqueued_at: Utc::now(),
+            trajectory: JointTrajectory::default(),
+            complete_condition: None,
+            limits: WatchdogLimits::try_from(&ExecutionTimeouts::default()).unwrap(),
+            acceptance_tx,
+        };
+        (goal, acceptance)
+    }
 
-            return Ok(cancel_handler);
+    fn queue_of(len: usize) -> (GoalQueue, Vec<Uuid>) {
+        let mut queue = GoalQueue::default();
+        for _ in 0..len {
+            queue.pending.push_back(pending_goal().0);
         }
+        let ids = queue.queued().iter().map(|goal| goal.queue_id).collect();
+        (queue, ids)
+    }
+
+    fn queue_ids(queue: &GoalQueue) -> Vec<Uuid> {
+        queue.queued().iter().map(|goal| goal.queue_id).collect()
+    }
 
-        Err(Error::NoValidGoalExists)
+    #[test]
+    fn reorder() {
+        let (mut queue, ids) = queue_of(3);
+        let order = vec![ids[2], ids[0], ids[1]];
+        queue.reorder(&order).unwrap();
+        assert_eq!(queue_ids(&queue), order);
+
+        let (mut empty, _) = queue_of(0);
+        empty.reorder(&[]).unwrap();
+    }
+
+    #[test]
+    fn reorder_keeps_the_goals() {
+        let mut queue = GoalQueue::default();
+        let (goal, mut acceptance) = pending_goal();
+        let id = goal.queue_id;
+        queue.pending.push_back(goal);
+        queue.pending.push_back(pending_goal().0);
+        let other = queue.pending[1].queue_id;
+
+        queue.reorder(&[other, id]).unwrap();
+        // the moved goal can still be answered
+        let goal = queue.pending.pop_back().unwrap();
+        assert_eq!(goal.queue_id, id);
+        goal.acceptance_tx.send(Err(Error::Dequeued(id))).unwrap();
+        assert!(matches!(acceptance.try_recv(), Ok(Err(Error::Dequeued(_)))));
+    }
+
+    #[test]
+    fn reorder_needs_a_permutation() {
+        let (mut queue, ids) = queue_of(3);
+        let invalid_orders = [
+            vec![ids[0], ids[1]],
+            vec![ids[0], ids[1], ids[1]],
+            vec![ids[0], ids[1], Uuid::new_v4()],
+            vec![ids[0], ids[1], ids[2], ids[2]],
+        ];
+        for order in invalid_orders {
+            assert!(matches!(
+                queue.reorder(&order),
+                Err(Error::QueueMismatch { .. })
+            ));
+            // the queue is unchanged
+            assert_eq!(queue_ids(&queue), ids);
+        }
     }
 }
@@ -0,0 +1,63 @@
+use crate::error::Error;
+use crate::models::robot::{RobotModel, UrdfSource};
+use arci_ros2::Node;
+use futures::stream::StreamExt;
+use r2r::{std_msgs, QosProfile};
+use std::time::Duration;
+use tokio::time::timeout;
+
+/// Reads the URDF from `source` and builds the robot model.
+pub async fn load_robot_model(node: &Node, source: &UrdfSource) -> Result<RobotModel, Error> {
+    let robot = match source {
+        UrdfSource::File { path } => {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || urdf_rs::read_file(path))
+                .await
+                .map_err(|e| Error::Other(e.into()))?
+        }
+        UrdfSource::Parameter { name } => urdf_rs::read_from_string(&read_parameter(node, name)?),
+        UrdfSource::Topic { topic, timeout_sec } => {
+            urdf_rs::read_from_string(&read_topic(node, topic, *timeout_sec).await?)
+        }
+    }?;
+    Ok(RobotModel::from_urdf(&robot))
+}
+
+fn read_parameter(node: &Node, name: &str) -> Result<String, Error> {
+    let node = node.r2r();
+    let params = node.params.lock().unwrap();
+    match params.get(name).map(|param| &param.value) {
+        Some(r2r::ParameterValue::String(urdf)) => Ok(urdf.clone()),
+        Some(value) => Err(Error::RobotDescriptionUnavailable(format!(
+            "parameter {} is not a string: {:?}",
+            name, value
+        ))),
+        None => Err(Error::RobotDescriptionUnavailable(format!(
+            "parameter {} is not set",
+            name
+        ))),
+    }
+}
+
+async fn read_topic(node: &Node, topic: &str, timeout_sec: f64) -> Result<String, Error> {
+    let wait = Duration::try_from_secs_f64(timeout_sec).map_err(|_| Error::InvalidTimeout {
+        name: "timeout_sec",
+        value: timeout_sec,
+    })?;
+    // the description is published once, so late subscribers need transient local
+    let mut subscriber = node
+        .r2r()
+        .subscribe::<std_msgs::msg::String>(topic, QosProfile::default().transient_local())?;
+
+    match timeout(wait, subscriber.next()).await {
+        Ok(Some(msg)) => Ok(msg.data),
+        Ok(None) => Err(Error::RobotDescriptionUnavailable(format!(
+            "subscription to {} was closed",
+            topic
+        ))),
+        Err(_) => Err(Error::RobotDescriptionUnavailable(format!(
+            "nothing was published on {} within {:?}",
+            topic, wait
+        ))),
+    }
+}
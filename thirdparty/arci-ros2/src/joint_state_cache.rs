@@ -0,0 +1,188 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use futures::stream::StreamExt;
+use r2r::{builtin_interfaces, sensor_msgs, QosProfile};
+
+use crate::Node;
+
+/// Latest state of a joint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JointStateEntry {
+    /// `None` if the publisher did not fill the field
+    pub position: Option<f64>,
+    pub velocity: Option<f64>,
+    pub effort: Option<f64>,
+    /// stamp of the `sensor_msgs/JointState` header
+    pub stamp: builtin_interfaces::msg::Time,
+    /// when the message was received
+    pub received_at: Instant,
+}
+
+/// Keeps the latest state of each joint published on a
+/// `sensor_msgs/JointState` topic.
+///
+/// States are merged per joint, so publishers sending a subset of the joints
+/// each are supported.
+#[derive(Clone, Default)]
+pub struct JointStateCache {
+    joints: Arc<Mutex<HashMap<String, JointStateEntry>>>,
+}
+
+impl JointStateCache {
+    /// Creates a new `JointStateCache` updated from `topic`. The subscription
+    /// ends when every clone of the cache has been dropped.
+    pub fn subscribe(node: &Node, topic: &str) -> Result<Self, arci::Error> {
+        let mut joint_state_sub = node
+            .r2r()
+            .subscribe::<sensor_msgs::msg::JointState>(topic, QosProfile::default())
+            .map_err(anyhow::Error::from)?;
+
+        let cache = Self::default();
+        let weak_joints = Arc::downgrade(&cache.joints);
+        tokio::spawn(async move {
+            while let Some(msg) = joint_state_sub.next().await {
+                let Some(joints) = weak_joints.upgrade() else {
+                    break;
+                };
+                Self { joints }.update(&msg);
+            }
+        });
+        Ok(cache)
+    }
+
+    /// Stores the state of the joints in `msg`.
+    pub fn update(&self, msg: &sensor_msgs::msg::JointState) {
+        let received_at = Instant::now();
+        let mut joints = self.joints.lock().unwrap();
+        for (i, name) in msg.name.iter().enumerate() {
+            joints.insert(
+                name.clone(),
+                JointStateEntry {
+                    position: msg.position.get(i).copied(),
+                    velocity: msg.velocity.get(i).copied(),
+                    effort: msg.effort.get(i).copied(),
+                    stamp: msg.header.stamp.clone(),
+                    received_at,
+                },
+            );
+        }
+    }
+
+    /// Returns true until the first message is received.
+    pub fn is_empty(&self) -> bool {
+        self.joints.lock().unwrap().is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<JointStateEntry> {
+        self.joints.lock().unwrap().get(name).cloned()
+    }
+
+    /// Returns the state of every joint received so far.
+    pub fn all(&self) -> HashMap<String, JointStateEntry> {
+        self.joints.lock().unwrap().clone()
+    }
+
+    /// Returns the positions of `joint_names`, in that order.
+    pub fn positions(&self, joint_names: &[String]) -> Result<Vec<f64>, arci::Error> {
+        let joints = self.joints.lock().unwrap();
+        if joints.is_empty() {
+            return Err(arci::Error::Uninitialized {
+                message: "No joint_state is available".to_string(),
+            });
+        }
+        joint_names
+            .iter()
+            .map(|name| {
+                joints
+                    .get(name)
+                    .and_then(|entry| entry.position)
+                    .ok_or_else(|| arci::Error::NoJoint(name.clone()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn joint_state(sec: i32, names: &[&str], positions: &[f64]) -> sensor_msgs::msg::JointState {
+        let mut msg = sensor_msgs::msg::JointState {
+            name: names.iter().map(|name| name.to_string()).collect(),
+            position: positions.to_vec(),
+            ..Default::default()
+        };
+        msg.header.stamp.sec = sec;
+        msg
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn partial_messages_are_merged() {
+        let cache = JointStateCache::default();
+        assert!(cache.is_empty());
+
+        // e.g. the arm and the gripper drivers
+        cache.update(&joint_state(1, &["shoulder", "elbow"], &[0.1, 0.2]));
+        cache.update(&joint_state(2, &["finger"], &[0.3]));
+        assert_eq!(
+            cache
+                .positions(&names(&["finger", "shoulder", "elbow"]))
+                .unwrap(),
+            vec![0.3, 0.1, 0.2]
+        );
+        assert_eq!(cache.all().len(), 3);
+        assert_eq!(cache.get("shoulder").unwrap().stamp.sec, 1);
+
+        // a newer message replaces only its joints
+        cache.update(&joint_state(3, &["elbow"], &[0.5]));
+        assert_eq!(
+            cache.positions(&names(&["shoulder", "elbow"])).unwrap(),
+            vec![0.1, 0.5]
+        );
+        assert_eq!(cache.get("shoulder").unwrap().stamp.sec, 1);
+        assert_eq!(cache.get("elbow").unwrap().stamp.sec, 3);
+    }
+
+    #[test]
+    fn missing_joints_are_reported() {
+        let cache = JointStateCache::default();
+        assert!(matches!(
+            cache.positions(&names(&["shoulder"])),
+            Err(arci::Error::Uninitialized { .. })
+        ));
+
+        cache.update(&joint_state(1, &["shoulder", "elbow"], &[0.1]));
+        assert!(cache.get("wrist").is_none());
+        assert!(matches!(
+            cache.positions(&names(&["shoulder", "wrist"])),
+            Err(arci::Error::NoJoint(name)) if name == "wrist"
+        ));
+        // no position was published for the elbow
+        assert_eq!(cache.get("elbow").unwrap().position, None);
+        assert!(matches!(
+            cache.positions(&names(&["elbow"])),
+            Err(arci::Error::NoJoint(name)) if name == "elbow"
+        ));
+    }
+
+    #[test]
+    fn entries_keep_the_reception_time() {
+        let cache = JointStateCache::default();
+        cache.update(&joint_state(1, &["shoulder"], &[0.1]));
+        let received_at = cache.get("shoulder").unwrap().received_at;
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        cache.update(&joint_state(2, &["elbow"], &[0.2]));
+
+        // the stale check of the gateway relies on this age
+        assert_eq!(cache.get("shoulder").unwrap().received_at, received_at);
+        assert!(cache.get("elbow").unwrap().received_at > received_at);
+    }
+}
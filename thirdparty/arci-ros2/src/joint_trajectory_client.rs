@@ -0,0 +1,170 @@
+use std::time::Duration;
+
+use arci::{JointTrajectoryClient, TrajectoryPoint, WaitFuture};
+use r2r::{
+    builtin_interfaces, control_msgs::action::FollowJointTrajectory, trajectory_msgs, GoalStatus,
+};
+
+use crate::{JointStateCache, Node};
+
+type FollowJointTrajectoryAction = FollowJointTrajectory::Action;
+
+const SUCCESSFUL: i32 = 0;
+
+/// `arci::JointTrajectoryClient` implementation backed by the
+/// FollowJointTrajectory action and `/joint_states`.
+#[derive(Clone)]
+pub struct Ros2JointTrajectoryClient {
+    joint_names: Vec<String>,
+    node: Node,
+    action_client: r2r::ActionClient<FollowJointTrajectoryAction>,
+    joint_state: JointStateCache,
+}
+
+impl Ros2JointTrajectoryClient {
+    /// Creates a new `Ros2JointTrajectoryClient`.
+    pub fn new(
+        node: Node,
+        action_name: &str,
+        joint_names: Vec<String>,
+    ) -> Result<Self, arci::Error> {
+        Self::with_joint_state_topic(node, action_name, joint_names, "/joint_states")
+    }
+
+    /// Creates a new `Ros2JointTrajectoryClient` which reads the current
+    /// positions from `joint_state_topic`.
+    pub fn with_joint_state_topic(
+        node: Node,
+        action_name: &str,
+        joint_names: Vec<String>,
+        joint_state_topic: &str,
+    ) -> Result<Self, arci::Error> {
+        let joint_state = JointStateCache::subscribe(&node, joint_state_topic)?;
+        Self::with_joint_state_cache(node, action_name, joint_names, joint_state)
+    }
+
+    /// Creates a new `Ros2JointTrajectoryClient` which reads the current
+    /// positions from a shared `JointStateCache`.
+    pub fn with_joint_state_cache(
+        node: Node,
+        action_name: &str,
+        joint_names: Vec<String>,
+        joint_state: JointStateCache,
+    ) -> Result<Self, arci::Error> {
+        let action_client = node
+            .r2r()
+            .create_action_client::<FollowJointTrajectoryAction>(action_name)
+            .map_err(anyhow::Error::from)?;
+
+        Ok(Self {
+            joint_names,
+            node,
+            action_client,
+            joint_state,
+        })
+    }
+
+    /// Returns a client of `joint_names` sharing the action client and the
+    /// joint states of this one.
+    pub fn with_joint_names(&self, joint_names: Vec<String>) -> Self {
+        Self {
+            joint_names,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the action client, e.g. to send goals with a header or
+    /// tolerances.
+    pub fn action_client(&self) -> &r2r::ActionClient<FollowJointTrajectoryAction> {
+        &self.action_client
+    }
+}
+
+impl JointTrajectoryClient for Ros2JointTrajectoryClient {
+    fn joint_names(&self) -> Vec<String> {
+        self.joint_names.clone()
+    }
+
+    fn current_joint_positions(&self) -> Result<Vec<f64>, arci::Error> {
+        self.joint_state.positions(&self.joint_names)
+    }
+
+    fn send_joint_positions(
+        &self,
+        positions: Vec<f64>,
+        duration: Duration,
+    ) -> Result<WaitFuture, arci::Error> {
+        self.send_joint_trajectory(vec![TrajectoryPoint::new(positions, duration)])
+    }
+
+    fn send_joint_trajectory(
+        &self,
+        trajectory: Vec<TrajectoryPoint>,
+    ) -> Result<WaitFuture, arci::Error> {
+        for point in &trajectory {
+            if point.positions.len() != self.joint_names.len() {
+                return Err(arci::Error::LengthMismatch {
+                    model: self.joint_names.len(),
+                    input: point.positions.len(),
+                });
+            }
+        }
+
+        let goal = FollowJointTrajectory::Goal {
+            trajectory: trajectory_msgs::msg::JointTrajectory {
+                joint_names: self.joint_names.clone(),
+                points: trajectory.into_iter().map(to_point_msg).collect(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let action_client = self.action_client.clone();
+        let is_available = self
+            .node
+            .r2r()
+            .is_available(&action_client)
+            .map_err(anyhow::Error::from)?;
+
+        let handle = tokio::spawn(async move {
+            is_available.await.map_err(|e| arci::Error::Connection {
+                message: format!("action server is not available: {e:?}"),
+            })?;
+            let (_goal, result, _feedback) = action_client
+                .send_goal_request(goal)
+                .map_err(anyhow::Error::from)?
+                .await
+                .map_err(|e| arci::Error::Connection {
+                    message: format!("goal rejected by action server: {e:?}"),
+                })?;
+            let (status, result) = result.await.map_err(anyhow::Error::from)?;
+            match status {
+                GoalStatus::Succeeded if result.error_code == SUCCESSFUL => Ok(()),
+                GoalStatus::Canceled => Err(arci::Error::Canceled {
+                    message: result.error_string,
+                }),
+                _ => Err(arci::Error::Other(anyhow::anyhow!(
+                    "action failed: status={status:?}, error_code={}, error_string={:?}",
+                    result.error_code,
+                    result.error_string
+                ))),
+            }
+        });
+
+        Ok(WaitFuture::new(async move {
+            handle.await.map_err(anyhow::Error::from)?
+        }))
+    }
+}
+
+fn to_point_msg(point: TrajectoryPoint) -> trajectory_msgs::msg::JointTrajectoryPoint {
+    trajectory_msgs::msg::JointTrajectoryPoint {
+        positions: point.positions,
+        velocities: point.velocities.unwrap_or_default(),
+        time_from_start: builtin_interfaces::msg::Duration {
+            sec: point.time_from_start.as_secs() as i32,
+            nanosec: point.time_from_start.subsec_nanos(),
+        },
+        ..Default::default()
+    }
+}
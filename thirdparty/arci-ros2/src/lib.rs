@@ -4,6 +4,8 @@
 // #![allow(missing_debug_implementations)] // TODO: Some r2r types don't implement Debug
 
 // mod cmd_vel_move_base;
+mod joint_state_cache;
+mod joint_trajectory_client;
 // mod navigation;
 pub mod node;
 // mod plugin;
@@ -15,6 +17,8 @@ pub mod node;
 // pub mod utils;
 
 // pub use cmd_vel_move_base::*;
+pub use joint_state_cache::*;
+pub use joint_trajectory_client::*;
 // pub use navigation::*;
 pub use crate::node::*;
 // pub use crate::node::*;
@@ -51,12 +51,19 @@ impl Node {
         }
         let node = self.clone();
         tokio::spawn(async move {
-            while Arc::strong_count(&node.inner) > 1 {
+            while node.inner.has_spin_thread.load(Ordering::Relaxed)
+                && Arc::strong_count(&node.inner) > 1
+            {
                 node.spin_once(interval).await;
             }
         });
     }
 
+    /// Stops the thread created by `run_spin_thread` after its current spin.
+    pub fn stop_spin_thread(&self) {
+        self.inner.has_spin_thread.store(false, Ordering::Relaxed);
+    }
+
     /// Spins the ROS2 node.
     pub async fn spin_once(&self, duration: Duration) {
         let now = std::time::Instant::now();
@@ -63,6 +63,34 @@ pub enum Error {
         position: f64,
         limit: RangeInclusive<f64>,
     },
+    #[error(
+        "arci: Velocity out of limit: joint={}, velocity={}, limit={}",
+        name,
+        velocity,
+        limit
+    )]
+    VelocityOutOfLimit {
+        name: String,
+        velocity: f64,
+        limit: f64,
+    },
+    #[error(
+        "arci: time_from_start of point {} ({}) is not after the previous point ({})",
+        index,
+        time_from_start,
+        previous
+    )]
+    InvalidTimeFromStart {
+        index: usize,
+        time_from_start: f64,
+        previous: f64,
+    },
+    #[error(
+        "arci: time_from_start {} is out of range [0, {}]",
+        time_from_start,
+        max
+    )]
+    TimeFromStartOutOfRange { time_from_start: f64, max: f64 },
     #[error("arci: Failed to construct instance: {}", .0)]
     Lazy(Arc<Error>),
     // #[error("arci: urdf: {:?}", .0)]
@@ -2,13 +2,13 @@
 
 // mod clients;
 mod error;
-// mod traits;
+mod traits;
 // pub mod utils;
 mod waits;
 
 // re-export
-// pub use async_trait::async_trait;
+pub use async_trait::async_trait;
 // pub use nalgebra::{self, Isometry2, Isometry3, UnitQuaternion, Vector2, Vector3};
 
-pub use crate::{error::*, waits::*};
+pub use crate::{error::*, traits::*, waits::*};
 // pub use crate::{clients::*, error::*, traits::*, waits::*};
@@ -0,0 +1,3 @@
+mod joint_trajectory_client;
+
+pub use joint_trajectory_client::*;
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use auto_impl::auto_impl;
+
+use crate::{error::Error, waits::WaitFuture};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrajectoryPoint {
+    pub positions: Vec<f64>,
+    pub velocities: Option<Vec<f64>>,
+    pub time_from_start: Duration,
+}
+
+impl TrajectoryPoint {
+    pub fn new(positions: Vec<f64>, time_from_start: Duration) -> Self {
+        Self {
+            positions,
+            velocities: None,
+            time_from_start,
+        }
+    }
+}
+
+#[auto_impl(Box, Arc)]
+pub trait JointTrajectoryClient: Send + Sync {
+    /// Returns names of joints.
+    fn joint_names(&self) -> Vec<String>;
+
+    /// Returns the current joint positions, in the order of `joint_names`.
+    fn current_joint_positions(&self) -> Result<Vec<f64>, Error>;
+
+    /// Sends the joint positions to reach after `duration`.
+    fn send_joint_positions(
+        &self,
+        positions: Vec<f64>,
+        duration: Duration,
+    ) -> Result<WaitFuture, Error>;
+
+    /// Sends the joint trajectory.
+    fn send_joint_trajectory(&self, trajectory: Vec<TrajectoryPoint>) -> Result<WaitFuture, Error>;
+}
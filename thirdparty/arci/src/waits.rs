@@ -2,18 +2,17 @@ use std::{
     fmt,
     pin::Pin,
     task::{Context, Poll},
-    // time::Duration,
+    time::Duration,
 };
 
-// use async_trait::async_trait;
-// use auto_impl::auto_impl;
+use async_trait::async_trait;
+use auto_impl::auto_impl;
 use futures::{
     future::{self, BoxFuture, Future, FutureExt},
     stream::{Stream, TryStreamExt},
 };
 
-// use crate::{error::Error, traits::JointTrajectoryClient};
-use crate::{error::Error};
+use crate::{error::Error, traits::JointTrajectoryClient};
 
 /// Waits until the underlying future is complete.
 #[must_use = "You must explicitly choose whether to wait for the complete or do not wait"]
@@ -78,122 +77,259 @@ impl fmt::Debug for WaitFuture {
     }
 }
 
-// #[async_trait]
-// #[auto_impl(Box, Arc)]
-// pub trait CompleteCondition: Send + Sync {
-//     async fn wait(
-//         &self,
-//         client: &dyn JointTrajectoryClient,
-//         target_positions: &[f64],
-//         duration_sec: f64,
-//     ) -> Result<(), Error>;
-// }
-
-// #[derive(Clone, Debug)]
-// pub struct TotalJointDiffCondition {
-//     pub allowable_error: f64,
-//     pub timeout_sec: f64,
-// }
-
-// impl TotalJointDiffCondition {
-//     pub fn new(allowable_error: f64, timeout_sec: f64) -> Self {
-//         Self {
-//             allowable_error,
-//             timeout_sec,
-//         }
-//     }
-// }
-
-// impl Default for TotalJointDiffCondition {
-//     fn default() -> Self {
-//         Self::new(0.02, 10.0)
-//     }
-// }
-
-// #[async_trait]
-// impl CompleteCondition for TotalJointDiffCondition {
-//     async fn wait(
-//         &self,
-//         client: &dyn JointTrajectoryClient,
-//         target_positions: &[f64],
-//         duration_sec: f64,
-//     ) -> Result<(), Error> {
-//         const CHECK_UNIT_SEC: f64 = 0.01;
-//         let check_unit_duration: Duration = Duration::from_secs_f64(CHECK_UNIT_SEC);
-//         let num_repeat: i32 = ((self.timeout_sec + duration_sec) / CHECK_UNIT_SEC) as i32;
-//         for _j in 0..num_repeat {
-//             let curs = client.current_joint_positions()?;
-//             let sum_err: f64 = target_positions
-//                 .iter()
-//                 .zip(curs.iter())
-//                 .map(|(tar, cur)| (tar - cur).abs())
-//                 .sum();
-//             if sum_err <= self.allowable_error {
-//                 return Ok(());
-//             }
-//             tokio::time::sleep(check_unit_duration).await;
-//         }
-//         Err(Error::TimeoutWithDiff {
-//             target: target_positions.to_vec(),
-//             current: client.current_joint_positions()?,
-//             is_reached: vec![false],
-//         })
-//     }
-// }
-
-// #[derive(Clone, Debug)]
-// pub struct EachJointDiffCondition {
-//     pub allowable_errors: Vec<f64>,
-//     pub timeout_sec: f64,
-// }
-
-// impl EachJointDiffCondition {
-//     pub fn new(allowable_errors: Vec<f64>, timeout_sec: f64) -> Self {
-//         Self {
-//             allowable_errors,
-//             timeout_sec,
-//         }
-//     }
-// }
-
-// #[async_trait]
-// impl CompleteCondition for EachJointDiffCondition {
-//     async fn wait(
-//         &self,
-//         client: &dyn JointTrajectoryClient,
-//         target_positions: &[f64],
-//         duration_sec: f64,
-//     ) -> Result<(), Error> {
-//         if target_positions.len() != self.allowable_errors.len() {
-//             eprintln!("wait_until_each_error_condition condition size mismatch");
-//             return Err(Error::LengthMismatch {
-//                 model: target_positions.len(),
-//                 input: self.allowable_errors.len(),
-//             });
-//         }
-//         let dof = target_positions.len();
-//         let mut is_reached = vec![false; dof];
-//         const CHECK_UNIT_SEC: f64 = 0.01;
-//         let check_unit_duration: Duration = Duration::from_secs_f64(CHECK_UNIT_SEC);
-//         let num_repeat: i32 = ((self.timeout_sec + duration_sec) / CHECK_UNIT_SEC) as i32;
-
-//         for _j in 0..num_repeat {
-//             for i in 0..dof {
-//                 let cur = client.current_joint_positions()?[i];
-//                 let tar = target_positions[i];
-//                 if !is_reached[i] {
-//                     is_reached[i] = (tar - cur).abs() < self.allowable_errors[i];
-//                 }
-//             }
-//             if !is_reached.contains(&false) {
-//                 return Ok(());
-//             }
-//             tokio::time::sleep(check_unit_duration).await;
-//         }
-//         Err(Error::TimeoutWithDiff {
-//             target: target_positions.to_vec(),
-//             current: client.current_joint_positions()?,
-//             is_reached,
-//         })
-//     }
-// }
+/// Condition deciding when a trajectory sent by a `JointTrajectoryClient` is
+/// complete.
+#[async_trait]
+#[auto_impl(Box, Arc)]
+pub trait CompleteCondition: Send + Sync {
+    /// Waits until the joints of `client` reach `target_positions`, for at
+    /// most the trajectory duration `duration_sec` plus the timeout of the
+    /// condition.
+    async fn wait(
+        &self,
+        client: &dyn JointTrajectoryClient,
+        target_positions: &[f64],
+        duration_sec: f64,
+    ) -> Result<(), Error>;
+}
+
+/// Complete once the sum of the position errors of all joints is at most
+/// `allowable_error`.
+#[derive(Clone, Debug)]
+pub struct TotalJointDiffCondition {
+    pub allowable_error: f64,
+    pub timeout_sec: f64,
+}
+
+impl TotalJointDiffCondition {
+    pub fn new(allowable_error: f64, timeout_sec: f64) -> Self {
+        Self {
+            allowable_error,
+            timeout_sec,
+        }
+    }
+}
+
+impl Default for TotalJointDiffCondition {
+    fn default() -> Self {
+        Self::new(0.02, 10.0)
+    }
+}
+
+#[async_trait]
+impl CompleteCondition for TotalJointDiffCondition {
+    async fn wait(
+        &self,
+        client: &dyn JointTrajectoryClient,
+        target_positions: &[f64],
+        duration_sec: f64,
+    ) -> Result<(), Error> {
+        const CHECK_UNIT_SEC: f64 = 0.01;
+        let check_unit_duration: Duration = Duration::from_secs_f64(CHECK_UNIT_SEC);
+        let num_repeat: i32 = ((self.timeout_sec + duration_sec) / CHECK_UNIT_SEC) as i32;
+        for _j in 0..num_repeat {
+            let curs = client.current_joint_positions()?;
+            check_length(target_positions, &curs)?;
+            let sum_err: f64 = target_positions
+                .iter()
+                .zip(curs.iter())
+                .map(|(tar, cur)| (tar - cur).abs())
+                .sum();
+            if sum_err <= self.allowable_error {
+                return Ok(());
+            }
+            tokio::time::sleep(check_unit_duration).await;
+        }
+        Err(Error::TimeoutWithDiff {
+            target: target_positions.to_vec(),
+            current: client.current_joint_positions()?,
+            is_reached: vec![false],
+        })
+    }
+}
+
+/// Complete once the position error of every joint has been below its
+/// `allowable_errors` entry, given in the order of the target positions.
+#[derive(Clone, Debug)]
+pub struct EachJointDiffCondition {
+    pub allowable_errors: Vec<f64>,
+    pub timeout_sec: f64,
+}
+
+impl EachJointDiffCondition {
+    pub fn new(allowable_errors: Vec<f64>, timeout_sec: f64) -> Self {
+        Self {
+            allowable_errors,
+            timeout_sec,
+        }
+    }
+}
+
+#[async_trait]
+impl CompleteCondition for EachJointDiffCondition {
+    async fn wait(
+        &self,
+        client: &dyn JointTrajectoryClient,
+        target_positions: &[f64],
+        duration_sec: f64,
+    ) -> Result<(), Error> {
+        if target_positions.len() != self.allowable_errors.len() {
+            return Err(Error::LengthMismatch {
+                model: target_positions.len(),
+                input: self.allowable_errors.len(),
+            });
+        }
+        let dof = target_positions.len();
+        let mut is_reached = vec![false; dof];
+        const CHECK_UNIT_SEC: f64 = 0.01;
+        let check_unit_duration: Duration = Duration::from_secs_f64(CHECK_UNIT_SEC);
+        let num_repeat: i32 = ((self.timeout_sec + duration_sec) / CHECK_UNIT_SEC) as i32;
+
+        for _j in 0..num_repeat {
+            let curs = client.current_joint_positions()?;
+            check_length(target_positions, &curs)?;
+            for i in 0..dof {
+                if !is_reached[i] {
+                    is_reached[i] =
+                        (target_positions[i] - curs[i]).abs() < self.allowable_errors[i];
+                }
+            }
+            if !is_reached.contains(&false) {
+                return Ok(());
+            }
+            tokio::time::sleep(check_unit_duration).await;
+        }
+        Err(Error::TimeoutWithDiff {
+            target: target_positions.to_vec(),
+            current: client.current_joint_positions()?,
+            is_reached,
+        })
+    }
+}
+
+fn check_length(target_positions: &[f64], current_positions: &[f64]) -> Result<(), Error> {
+    if target_positions.len() != current_positions.len() {
+        return Err(Error::LengthMismatch {
+            model: current_positions.len(),
+            input: target_positions.len(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::TrajectoryPoint;
+
+    struct DummyClient {
+        positions: Mutex<Vec<f64>>,
+    }
+
+    impl DummyClient {
+        fn new(positions: Vec<f64>) -> Self {
+            Self {
+                positions: Mutex::new(positions),
+            }
+        }
+    }
+
+    impl JointTrajectoryClient for DummyClient {
+        fn joint_names(&self) -> Vec<String> {
+            (0..self.positions.lock().unwrap().len())
+                .map(|i| format!("joint{i}"))
+                .collect()
+        }
+
+        fn current_joint_positions(&self) -> Result<Vec<f64>, Error> {
+            Ok(self.positions.lock().unwrap().clone())
+        }
+
+        fn send_joint_positions(
+            &self,
+            positions: Vec<f64>,
+            _duration: Duration,
+        ) -> Result<WaitFuture, Error> {
+            *self.positions.lock().unwrap() = positions;
+            Ok(WaitFuture::ready())
+        }
+
+        fn send_joint_trajectory(
+            &self,
+            trajectory: Vec<TrajectoryPoint>,
+        ) -> Result<WaitFuture, Error> {
+            if let Some(last) = trajectory.last() {
+                *self.positions.lock().unwrap() = last.positions.clone();
+            }
+            Ok(WaitFuture::ready())
+        }
+    }
+
+    #[tokio::test]
+    async fn total_joint_diff() {
+        let client = DummyClient::new(vec![0.0, 1.0]);
+        let condition = TotalJointDiffCondition::new(0.1, 0.05);
+        condition.wait(&client, &[0.04, 1.04], 0.0).await.unwrap();
+        match condition.wait(&client, &[0.2, 1.0], 0.0).await {
+            Err(Error::TimeoutWithDiff {
+                target, current, ..
+            }) => {
+                assert_eq!(target, vec![0.2, 1.0]);
+                assert_eq!(current, vec![0.0, 1.0]);
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn each_joint_diff() {
+        let client = DummyClient::new(vec![0.0, 1.0]);
+        let condition = EachJointDiffCondition::new(vec![0.1, 0.5], 0.05);
+        condition.wait(&client, &[0.05, 1.4], 0.0).await.unwrap();
+        match condition.wait(&client, &[0.2, 1.0], 0.0).await {
+            Err(Error::TimeoutWithDiff { is_reached, .. }) => {
+                assert_eq!(is_reached, vec![false, true]);
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn waits_until_reached() {
+        let client = std::sync::Arc::new(DummyClient::new(vec![0.0]));
+        let condition = EachJointDiffCondition::new(vec![0.1], 1.0);
+        let moving = client.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            moving
+                .send_joint_positions(vec![1.0], Duration::ZERO)
+                .unwrap()
+                .await
+                .unwrap();
+        });
+        condition.wait(&*client, &[1.0], 0.0).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn length_mismatch() {
+        let client = DummyClient::new(vec![0.0, 1.0]);
+        let each = EachJointDiffCondition::new(vec![0.1], 0.05);
+        assert!(matches!(
+            each.wait(&client, &[0.0, 1.0], 0.0).await,
+            Err(Error::LengthMismatch { model: 2, input: 1 })
+        ));
+        // more targets than joints of the client
+        let each = EachJointDiffCondition::new(vec![0.1; 3], 0.05);
+        assert!(matches!(
+            each.wait(&client, &[0.0, 1.0, 2.0], 0.0).await,
+            Err(Error::LengthMismatch { model: 2, input: 3 })
+        ));
+        let total = TotalJointDiffCondition::new(0.1, 0.05);
+        assert!(matches!(
+            total.wait(&client, &[0.0], 0.0).await,
+            Err(Error::LengthMismatch { model: 2, input: 1 })
+        ));
+    }
+}